@@ -1,7 +1,7 @@
 use std::{
     ffi::OsStr,
     fmt::{Display, Formatter},
-    io,
+    io::{self, BufRead, BufReader},
     process::{Command, Output, Stdio},
     string::FromUtf8Error,
 };
@@ -11,6 +11,7 @@ use console::style;
 use crate::{
     config::global_config,
     logger::{self},
+    output,
 };
 
 /// A wrapper around [`xshell::Cmd`] that allows for improved error handling,
@@ -95,9 +96,15 @@ impl<'a> Cmd<'a> {
         self
     }
 
-    /// Run the command without capturing its output.
+    /// Run the command without capturing its output. With `--dry-run`, the command is only
+    /// logged.
     pub fn run(mut self) -> CmdResult<()> {
         let command_txt = self.inner.to_string();
+        if global_config().is_dry_run() {
+            log_dry_run(&command_txt);
+            return Ok(());
+        }
+
         let output = if global_config().verbose || self.force_run {
             logger::debug(format!("Running: {}", self.inner));
             logger::new_empty_line();
@@ -124,7 +131,8 @@ impl<'a> Cmd<'a> {
         Ok(())
     }
 
-    /// Run the command and return its output.
+    /// Run the command and return its output. It's also run with `--dry-run`, so it must only
+    /// be used for commands that don't change anything, like queries.
     pub fn run_with_output(&mut self) -> CmdResult<std::process::Output> {
         if global_config().verbose || self.force_run {
             logger::debug(format!("Running: {}", self.inner));
@@ -142,6 +150,44 @@ impl<'a> Cmd<'a> {
 
         Ok(output)
     }
+
+    /// Run the command calling `on_line` for every line written to stdout, as soon as it's
+    /// available. Stderr is forwarded to the console. With `--dry-run`, the command is only
+    /// logged.
+    pub fn run_with_line_handler(self, mut on_line: impl FnMut(&str)) -> CmdResult<()> {
+        let command_txt = self.inner.to_string();
+        if global_config().is_dry_run() {
+            log_dry_run(&command_txt);
+            return Ok(());
+        }
+        if global_config().verbose {
+            logger::debug(format!("Running: {}", command_txt));
+        }
+
+        let mut command: Command = self.inner.into();
+        command.stdout(Stdio::piped()).stderr(Stdio::inherit());
+        let mut child = command.spawn()?;
+
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                on_line(&line?);
+            }
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(CmdError {
+                stderr: None,
+                source: anyhow::anyhow!("Command failed to run: {}", command_txt),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn log_dry_run(command_text: &str) {
+    logger::step(format!("Dry run, skipping: {}", style(command_text).bold()));
 }
 
 fn check_output_status(command_text: &str, output: &std::process::Output) -> CmdResult<()> {
@@ -158,7 +204,12 @@ fn check_output_status(command_text: &str, output: &std::process::Output) -> Cmd
 }
 
 fn run_low_level_process_command(mut command: Command, piped_std_err: bool) -> io::Result<Output> {
-    command.stdout(Stdio::inherit());
+    // Stdout only holds the events with `--output json`
+    if output::is_json() {
+        command.stdout(io::stderr());
+    } else {
+        command.stdout(Stdio::inherit());
+    }
     if piped_std_err {
         command.stderr(Stdio::piped());
     } else {
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use once_cell::sync::OnceCell;
 
+use crate::{output::OutputFormat, runtime::RuntimeKind};
+
 static CONFIG: OnceCell<GlobalConfig> = OnceCell::new();
 
 pub fn init_global_config(config: GlobalConfig) {
@@ -12,9 +16,29 @@ pub fn global_config() -> &'static GlobalConfig {
     CONFIG.get().expect("GlobalConfig not initialized")
 }
 
+/// The global configuration, if it was already initialized
+pub fn try_global_config() -> Option<&'static GlobalConfig> {
+    CONFIG.get()
+}
+
 #[derive(Debug)]
 pub struct GlobalConfig {
     pub verbose: bool,
+    /// Directory the CLI runs from, holding the assets and the generated state
+    pub workspace: PathBuf,
     pub config_file: Option<String>,
+    pub overrides: Vec<String>,
     pub default: bool,
+    /// With `--dry-run`, directory the deployments are rendered into. Commands changing the
+    /// state of Docker are logged instead of being run.
+    pub dry_run: Option<PathBuf>,
+    /// Container runtime given with `--runtime`, detected when not set
+    pub runtime: Option<RuntimeKind>,
+    pub output: OutputFormat,
+}
+
+impl GlobalConfig {
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
 }
@@ -1,62 +1,145 @@
-use url::Url;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
 use xshell::{cmd, Shell};
 
-use crate::cmd::Cmd;
+use crate::{
+    cmd::Cmd,
+    runtime::{first_line, version_output, ContainerRuntime, RuntimeKind},
+};
 
-pub fn up(shell: &Shell, docker_compose_file: &str, detach: bool) -> anyhow::Result<()> {
-    let args = if detach { vec!["-d"] } else { vec![] };
-    let mut cmd = Cmd::new(cmd!(
-        shell,
-        "docker compose -f {docker_compose_file} up {args...}"
-    ));
-    cmd = if !detach { cmd.with_force_run() } else { cmd };
-    Ok(cmd.run()?)
+/// A container as reported by `docker compose ps --format json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ComposeContainer {
+    pub name: String,
+    pub service: String,
+    pub state: String,
+    #[serde(default)]
+    pub health: String,
+    #[serde(default)]
+    pub exit_code: i32,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub publishers: Option<Vec<ComposePublisher>>,
 }
 
-pub fn down(shell: &Shell, docker_compose_file: &str) -> anyhow::Result<()> {
-    Ok(Cmd::new(cmd!(
-        shell,
-        "docker compose -f {docker_compose_file} down -v"
-    ))
-    .run()?)
+/// A port published by a compose container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ComposePublisher {
+    #[serde(rename = "URL", default)]
+    pub url: String,
+    pub target_port: u16,
+    pub published_port: u16,
+    pub protocol: String,
 }
 
-pub fn run(shell: &Shell, docker_image: &str, docker_args: Vec<String>) -> anyhow::Result<()> {
-    Ok(Cmd::new(cmd!(shell, "docker run {docker_args...} {docker_image}")).run()?)
+/// Options for `compose logs`
+#[derive(Debug, Default)]
+pub struct LogsOptions {
+    /// Services to show, all of them if empty
+    pub services: Vec<String>,
+    pub follow: bool,
+    pub since: Option<String>,
+    pub tail: Option<u32>,
 }
 
-pub fn run_command(
-    shell: &Shell,
-    docker_image: &str,
-    docker_args: Vec<String>,
-    docker_command: Vec<String>,
-) -> anyhow::Result<()> {
-    Ok(Cmd::new(cmd!(
-        shell,
-        "docker run {docker_args...} {docker_image} {docker_command...}"
-    ))
-    .run()?)
-}
+/// Docker with the `docker compose` plugin
+pub struct Docker;
 
-pub fn exec_in_container(
-    shell: &Shell,
-    container_name: &str,
-    command: Vec<String>,
-) -> anyhow::Result<()> {
-    Ok(Cmd::new(cmd!(shell, "docker exec {container_name} {command...}")).run()?)
-}
+impl ContainerRuntime for Docker {
+    fn kind(&self) -> RuntimeKind {
+        RuntimeKind::Docker
+    }
+
+    fn binary(&self) -> &'static str {
+        "docker"
+    }
+
+    fn compose<'a>(&self, shell: &'a Shell, compose_file: &str) -> xshell::Cmd<'a> {
+        cmd!(shell, "docker compose -f {compose_file}")
+    }
 
-pub fn adjust_localhost_for_docker(mut url: Url) -> anyhow::Result<Url> {
-    if let Some(host) = url.host_str() {
-        if host == "localhost" || host == "127.0.0.1" {
-            url.set_host(Some("host.docker.internal"))?;
+    fn host_gateway(&self) -> &'static str {
+        "host.docker.internal"
+    }
+
+    fn parse_ps_output(&self, output: &str) -> anyhow::Result<Vec<ComposeContainer>> {
+        parse_ps_output(output)
+    }
+
+    fn client_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        let format = "{{.Client.Version}}";
+        let output = Cmd::new(cmd!(shell, "docker version --format {format}")).run_with_output()?;
+        // `docker version` exits with an error without a daemon, after printing the client part
+        match String::from_utf8(output.stdout)?.trim() {
+            "" => bail!("{}", first_line(&output.stderr)),
+            version => Ok(version.to_string()),
         }
-    } else {
-        anyhow::bail!("Failed to parse: no host");
     }
-    Ok(url)
+
+    fn server_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        let format = "{{.Server.Version}}";
+        version_output(Cmd::new(cmd!(shell, "docker version --format {format}")))
+    }
+
+    fn compose_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        version_output(Cmd::new(cmd!(shell, "docker compose version --short")))
+    }
 }
 
-pub fn build_image(shell: &Shell, path: String, name: String) -> anyhow::Result<()> {
-    Ok(Cmd::new(cmd!(shell, "docker build -t {name} {path}")).run()?)
+// Depending on the Compose version, `ps --format json` prints either a JSON array or one
+// JSON object per line
+pub(crate) fn parse_ps_output(output: &str) -> anyhow::Result<Vec<ComposeContainer>> {
+    let output = output.trim();
+    if output.starts_with('[') {
+        return Ok(serde_json::from_str(output)?);
+    }
+
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MADARA_CONTAINER: &str = r#"{"Command":"\"/usr/local/bin/runner.sh\"","CreatedAt":"2025-04-24 10:00:00 +0000 UTC","ExitCode":0,"Health":"healthy","ID":"7f1c","Image":"madara:latest","Labels":"","LocalVolumes":"0","Mounts":"","Name":"madara_runner","Names":"madara_runner","Networks":"madara_runner_default","Ports":"0.0.0.0:9944->9944/tcp","Project":"madara_runner","Publishers":[{"URL":"0.0.0.0","TargetPort":9944,"PublishedPort":9944,"Protocol":"tcp"}],"RunningFor":"5 minutes ago","Service":"madara","Size":"0B","State":"running","Status":"Up 5 minutes (healthy)"}"#;
+    const BOOTSTRAPPER_CONTAINER: &str = r#"{"ExitCode":1,"Health":"","Name":"bootstrapper_l2","Publishers":null,"Service":"bootstrapper_l2","State":"exited","Status":"Exited (1) 2 minutes ago"}"#;
+
+    #[test]
+    fn test_parse_ps_output_lines() {
+        let output = format!("{}\n{}\n", MADARA_CONTAINER, BOOTSTRAPPER_CONTAINER);
+        let containers = parse_ps_output(&output).unwrap();
+
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].service, "madara");
+        assert_eq!(containers[0].health, "healthy");
+        assert_eq!(
+            containers[0].publishers.as_ref().unwrap()[0].published_port,
+            9944
+        );
+        assert_eq!(containers[1].state, "exited");
+        assert_eq!(containers[1].exit_code, 1);
+        assert!(containers[1].publishers.is_none());
+    }
+
+    #[test]
+    fn test_parse_ps_output_array() {
+        let output = format!("[{},{}]", MADARA_CONTAINER, BOOTSTRAPPER_CONTAINER);
+        let containers = parse_ps_output(&output).unwrap();
+
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[1].name, "bootstrapper_l2");
+    }
+
+    #[test]
+    fn test_parse_ps_output_empty() {
+        assert!(parse_ps_output("").unwrap().is_empty());
+        assert!(parse_ps_output("[]").unwrap().is_empty());
+    }
 }
@@ -4,6 +4,9 @@ mod term;
 pub mod cmd;
 pub mod config;
 pub mod docker;
+pub mod podman;
+pub mod rpc;
+pub mod runtime;
 
 pub use prompt::{init_prompt_theme, validation, Prompt, PromptConfirm, PromptSelect};
-pub use term::{error, logger, spinner};
+pub use term::{error, logger, output, spinner};
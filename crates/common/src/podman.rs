@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use xshell::{cmd, Shell};
+
+use crate::{
+    cmd::Cmd,
+    docker::{self, ComposeContainer, ComposePublisher},
+    runtime::{first_line, on_path, version_output, ContainerRuntime, RuntimeKind},
+};
+
+// Label set on the containers by both `docker-compose` and `podman-compose`
+const SERVICE_LABEL: &str = "com.docker.compose.service";
+
+/// Podman, usually rootless
+pub struct Podman {
+    compose: PodmanCompose,
+}
+
+/// How compose files are run with Podman
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PodmanCompose {
+    /// `podman compose`, which delegates to `docker-compose` or `podman-compose`
+    Plugin,
+    /// `podman-compose`, for Podman versions without `podman compose`
+    Standalone,
+}
+
+impl Podman {
+    /// `podman compose` runs `docker-compose` first when it's installed, so `podman-compose`
+    /// is only used directly when it's the only provider. This also covers Podman versions
+    /// older than 4.7, which don't have `podman compose`.
+    pub fn detect() -> Self {
+        let compose = if on_path("podman-compose") && !on_path("docker-compose") {
+            PodmanCompose::Standalone
+        } else {
+            PodmanCompose::Plugin
+        };
+        Self { compose }
+    }
+
+    fn compose_version_cmd<'a>(&self, shell: &'a Shell) -> xshell::Cmd<'a> {
+        match self.compose {
+            PodmanCompose::Plugin => cmd!(shell, "podman compose version"),
+            PodmanCompose::Standalone => cmd!(shell, "podman-compose version"),
+        }
+    }
+}
+
+impl ContainerRuntime for Podman {
+    fn kind(&self) -> RuntimeKind {
+        RuntimeKind::Podman
+    }
+
+    fn binary(&self) -> &'static str {
+        "podman"
+    }
+
+    fn compose<'a>(&self, shell: &'a Shell, compose_file: &str) -> xshell::Cmd<'a> {
+        match self.compose {
+            PodmanCompose::Plugin => cmd!(shell, "podman compose -f {compose_file}"),
+            PodmanCompose::Standalone => cmd!(shell, "podman-compose -f {compose_file}"),
+        }
+    }
+
+    fn host_gateway(&self) -> &'static str {
+        "host.containers.internal"
+    }
+
+    /// `docker-compose` prints the compose format while `podman-compose` forwards to
+    /// `podman ps`, which has its own
+    fn parse_ps_output(&self, output: &str) -> anyhow::Result<Vec<ComposeContainer>> {
+        docker::parse_ps_output(output).or_else(|_| parse_podman_ps_output(output))
+    }
+
+    fn client_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        let format = "{{.Client.Version}}";
+        version_output(Cmd::new(cmd!(shell, "podman version --format {format}")))
+    }
+
+    /// Podman has no daemon, but on macOS and Windows the containers run in a machine that
+    /// must be started
+    fn server_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        let format = "{{.Version.Version}}";
+        version_output(Cmd::new(cmd!(shell, "podman info --format {format}")))
+    }
+
+    fn compose_version(&self, shell: &Shell) -> anyhow::Result<String> {
+        let mut cmd = Cmd::new(self.compose_version_cmd(shell));
+        let output = cmd.run_with_output()?;
+        if !output.status.success() {
+            bail!("{}", first_line(&output.stderr));
+        }
+        parse_compose_version(&String::from_utf8(output.stdout)?)
+            .context("Unable to find the compose version")
+    }
+}
+
+/// A container as reported by `podman ps --format json`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PodmanContainer {
+    names: Vec<String>,
+    state: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    exit_code: i32,
+    #[serde(default)]
+    labels: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    ports: Option<Vec<PodmanPort>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodmanPort {
+    #[serde(default)]
+    host_ip: String,
+    container_port: u16,
+    host_port: u16,
+    protocol: String,
+}
+
+impl From<PodmanContainer> for ComposeContainer {
+    fn from(container: PodmanContainer) -> Self {
+        let service = container
+            .labels
+            .and_then(|mut labels| labels.remove(SERVICE_LABEL))
+            .unwrap_or_default();
+        let publishers = container.ports.map(|ports| {
+            ports
+                .into_iter()
+                .map(|port| ComposePublisher {
+                    url: port.host_ip,
+                    target_port: port.container_port,
+                    published_port: port.host_port,
+                    protocol: port.protocol,
+                })
+                .collect()
+        });
+
+        Self {
+            name: container.names.into_iter().next().unwrap_or_default(),
+            service,
+            state: container.state.to_lowercase(),
+            health: health_from_status(&container.status),
+            exit_code: container.exit_code,
+            status: container.status,
+            publishers,
+        }
+    }
+}
+
+fn parse_podman_ps_output(output: &str) -> anyhow::Result<Vec<ComposeContainer>> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(vec![]);
+    }
+    let containers: Vec<PodmanContainer> = serde_json::from_str(output)?;
+    Ok(containers.into_iter().map(Into::into).collect())
+}
+
+// `podman ps` has no health field, it's only shown in the status, e.g. `Up 5 minutes (healthy)`
+fn health_from_status(status: &str) -> String {
+    ["unhealthy", "healthy", "starting"]
+        .into_iter()
+        .find(|health| status.contains(&format!("({})", health)))
+        .unwrap_or_default()
+        .to_string()
+}
+
+// The output depends on the compose provider, e.g. `Docker Compose version v2.24.0` or
+// `podman-compose version 1.0.6` followed by the Podman version
+fn parse_compose_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find(|line| {
+            let line = line.to_lowercase();
+            line.contains("compose") && line.contains("version")
+        })
+        .and_then(|line| line.split_whitespace().last())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MADARA_CONTAINER: &str = r#"{"Command":["/usr/local/bin/runner.sh"],"ExitCode":0,"Id":"7f1c","Image":"localhost/madara:latest","Labels":{"com.docker.compose.project":"devnet","com.docker.compose.service":"madara"},"Names":["devnet_madara"],"Ports":[{"host_ip":"","container_port":9944,"host_port":9944,"range":1,"protocol":"tcp"}],"State":"running","Status":"Up 5 minutes (healthy)"}"#;
+    const BOOTSTRAPPER_CONTAINER: &str = r#"{"ExitCode":1,"Labels":{"com.docker.compose.service":"bootstrapper_l2"},"Names":["devnet_bootstrapper_l2"],"Ports":null,"State":"exited","Status":"Exited (1) 2 minutes ago"}"#;
+
+    #[test]
+    fn test_parse_podman_ps_output() {
+        let podman = Podman {
+            compose: PodmanCompose::Standalone,
+        };
+        let output = format!("[{},{}]", MADARA_CONTAINER, BOOTSTRAPPER_CONTAINER);
+        let containers = podman.parse_ps_output(&output).unwrap();
+
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].name, "devnet_madara");
+        assert_eq!(containers[0].service, "madara");
+        assert_eq!(containers[0].health, "healthy");
+        assert_eq!(
+            containers[0].publishers.as_ref().unwrap()[0].published_port,
+            9944
+        );
+        assert_eq!(containers[1].state, "exited");
+        assert_eq!(containers[1].exit_code, 1);
+        assert_eq!(containers[1].health, "");
+        assert!(podman.parse_ps_output("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_compose_version() {
+        assert_eq!(
+            parse_compose_version("Docker Compose version v2.24.0\n").as_deref(),
+            Some("v2.24.0")
+        );
+        assert_eq!(
+            parse_compose_version("podman-compose version 1.0.6\npodman version 4.9.3\n")
+                .as_deref(),
+            Some("1.0.6")
+        );
+        assert_eq!(parse_compose_version("podman version 4.9.3"), None);
+    }
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use cliclack::{Input, Validate};
+use cliclack::Input;
 
 pub struct Prompt {
     inner: Input,
@@ -27,21 +27,23 @@ impl Prompt {
         self
     }
 
-    pub fn validate_with<F>(mut self, f: F) -> Self
+    pub fn validate_with<F, E>(mut self, f: F) -> Self
     where
-        F: Validate<String> + 'static,
-        F::Err: ToString,
+        F: Fn(&str) -> Result<(), E> + 'static,
+        E: ToString,
     {
-        self.inner = self.inner.validate(f);
+        self.inner = self.inner.validate(move |val: &String| f(val));
         self
     }
 
-    pub fn validate_interactively<F>(mut self, f: F) -> Self
+    pub fn validate_interactively<F, E>(mut self, f: F) -> Self
     where
-        F: Validate<String> + 'static,
-        F::Err: ToString,
+        F: Fn(&str) -> Result<(), E> + 'static,
+        E: ToString,
     {
-        self.inner = self.inner.validate_interactively(f);
+        self.inner = self
+            .inner
+            .validate_interactively(move |val: &String| f(val));
         self
     }
 
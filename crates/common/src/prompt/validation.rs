@@ -1,7 +1,7 @@
 use std::{path::PathBuf, str::FromStr};
 use url::Url;
 
-pub fn validate_filename(val: &String) -> Result<(), String> {
+pub fn validate_filename(val: &str) -> Result<(), String> {
     // Empty check (if needed, based on your requirements)
     if val.is_empty() {
         return Ok(());
@@ -39,7 +39,7 @@ pub fn validate_filename(val: &String) -> Result<(), String> {
     Ok(())
 }
 
-pub fn validate_url(val: &String) -> Result<(), String> {
+pub fn validate_url(val: &str) -> Result<(), String> {
     // Empty check
     if val.is_empty() {
         return Ok(());
@@ -156,15 +156,87 @@ fn validate_hex_string(val: &str, expected_bytes: usize, field_name: &str) -> Re
     Ok(())
 }
 
-pub fn validate_eth_address(val: &String) -> Result<(), String> {
+pub fn validate_eth_address(val: &str) -> Result<(), String> {
     validate_hex_string(val, 20, "Ethereum address")
 }
 
-pub fn validate_private_key(val: &String) -> Result<(), String> {
+pub fn validate_private_key(val: &str) -> Result<(), String> {
     validate_hex_string(val, 32, "Private key")
 }
 
-pub fn validate_u64(val: &String) -> Result<(), String> {
+/// Prime of the Starknet field, in hexadecimal without its `0x` prefix
+const FELT_PRIME_HEX: &str = "800000000000011000000000000000000000000000000000000000000000001";
+
+fn validate_felt_string(val: &str, field_name: &str) -> Result<(), String> {
+    // Empty check
+    if val.is_empty() {
+        return Ok(());
+    }
+
+    let Some(hex) = val.strip_prefix("0x") else {
+        return Err(format!("{} must start with '0x'", field_name));
+    };
+
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "{} must contain only hexadecimal characters",
+            field_name
+        ));
+    }
+
+    // Leading zeros can be omitted
+    if hex.len() > 64 {
+        return Err(format!(
+            "{} must be at most 66 characters long (including '0x')",
+            field_name
+        ));
+    }
+
+    // Same number of digits, the hexadecimal strings compare like the numbers
+    let digits = hex.trim_start_matches('0').to_ascii_lowercase();
+    if digits.len() > FELT_PRIME_HEX.len()
+        || (digits.len() == FELT_PRIME_HEX.len() && digits.as_str() >= FELT_PRIME_HEX)
+    {
+        return Err(format!(
+            "{} must be lower than the Starknet field prime (0x{})",
+            field_name, FELT_PRIME_HEX
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_starknet_address(val: &str) -> Result<(), String> {
+    validate_felt_string(val, "Starknet address")
+}
+
+/// Field element, like Starknet private keys and hashes
+pub fn validate_felt(val: &str) -> Result<(), String> {
+    validate_felt_string(val, "Value")
+}
+
+pub fn validate_version(val: &str) -> Result<(), String> {
+    // Empty check
+    if val.is_empty() {
+        return Ok(());
+    }
+
+    // Starknet versions have 3 or 4 numeric parts, e.g. 0.13.2 or 0.13.2.1
+    let parts = val.split('.').collect::<Vec<_>>();
+    if !(3..=4).contains(&parts.len())
+        || parts
+            .iter()
+            .any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(
+            "Version must be made of 3 or 4 numbers separated by dots (e.g. 0.13.2)".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn validate_u64(val: &str) -> Result<(), String> {
     // Empty check
     if val.is_empty() {
         return Ok(());
@@ -177,7 +249,7 @@ pub fn validate_u64(val: &String) -> Result<(), String> {
     }
 }
 
-pub fn validate_time_with_unit(val: &String) -> Result<(), String> {
+pub fn validate_time_with_unit(val: &str) -> Result<(), String> {
     // Empty check
     if val.is_empty() {
         return Ok(());
@@ -195,7 +267,7 @@ pub fn validate_time_with_unit(val: &String) -> Result<(), String> {
     if !number_str
         .chars()
         .next()
-        .map_or(false, |c| c.is_ascii_digit())
+        .is_some_and(|c| c.is_ascii_digit())
     {
         return Err("Time value must start with a number".to_string());
     }
@@ -233,31 +305,31 @@ mod tests {
     #[test]
     fn test_validate_filename() {
         // Valid cases
-        assert!(validate_filename(&"".to_string()).is_ok());
-        assert!(validate_filename(&"config.toml".to_string()).is_ok());
-        assert!(validate_filename(&"my_config_123.toml".to_string()).is_ok());
-        assert!(validate_filename(&"complex-name_with-symbols.toml".to_string()).is_ok());
+        assert!(validate_filename("").is_ok());
+        assert!(validate_filename("config.toml").is_ok());
+        assert!(validate_filename("my_config_123.toml").is_ok());
+        assert!(validate_filename("complex-name_with-symbols.toml").is_ok());
 
         // Invalid cases - wrong extension
-        assert!(validate_filename(&"config.txt".to_string()).is_err());
-        assert!(validate_filename(&"config".to_string()).is_err());
-        assert!(validate_filename(&"config.toml.txt".to_string()).is_err());
+        assert!(validate_filename("config.txt").is_err());
+        assert!(validate_filename("config").is_err());
+        assert!(validate_filename("config.toml.txt").is_err());
 
         // Invalid cases - spaces
-        assert!(validate_filename(&" config.toml".to_string()).is_err());
-        assert!(validate_filename(&"config.toml ".to_string()).is_err());
-        assert!(validate_filename(&" config.toml ".to_string()).is_err());
+        assert!(validate_filename(" config.toml").is_err());
+        assert!(validate_filename("config.toml ").is_err());
+        assert!(validate_filename(" config.toml ").is_err());
 
         // Invalid cases - special characters
-        assert!(validate_filename(&"config/.toml".to_string()).is_err());
-        assert!(validate_filename(&"config\\.toml".to_string()).is_err());
-        assert!(validate_filename(&"config<.toml".to_string()).is_err());
-        assert!(validate_filename(&"config>.toml".to_string()).is_err());
-        assert!(validate_filename(&"config:.toml".to_string()).is_err());
-        assert!(validate_filename(&"config\".toml".to_string()).is_err());
-        assert!(validate_filename(&"config|.toml".to_string()).is_err());
-        assert!(validate_filename(&"config?.toml".to_string()).is_err());
-        assert!(validate_filename(&"config*.toml".to_string()).is_err());
+        assert!(validate_filename("config/.toml").is_err());
+        assert!(validate_filename("config\\.toml").is_err());
+        assert!(validate_filename("config<.toml").is_err());
+        assert!(validate_filename("config>.toml").is_err());
+        assert!(validate_filename("config:.toml").is_err());
+        assert!(validate_filename("config\".toml").is_err());
+        assert!(validate_filename("config|.toml").is_err());
+        assert!(validate_filename("config?.toml").is_err());
+        assert!(validate_filename("config*.toml").is_err());
 
         // Invalid cases - length
         assert!(validate_filename(&format!("{}.toml", "a".repeat(252))).is_err());
@@ -266,18 +338,18 @@ mod tests {
     #[test]
     fn test_validate_url() {
         // Valid URLs
-        assert!(validate_url(&"http://localhost:8545".to_string()).is_ok());
-        assert!(validate_url(&"https://example.com:8545".to_string()).is_ok());
-        assert!(validate_url(&"wss://eth-sepolia.g.alchemy.com/v2/APIKEY".to_string()).is_ok());
-        assert!(validate_url(&"http://anvil:8545".to_string()).is_ok());
-        assert!(validate_url(&"http://127.0.0.1:8545".to_string()).is_ok());
-        assert!(validate_url(&"".to_string()).is_ok());
+        assert!(validate_url("http://localhost:8545").is_ok());
+        assert!(validate_url("https://example.com:8545").is_ok());
+        assert!(validate_url("wss://eth-sepolia.g.alchemy.com/v2/APIKEY").is_ok());
+        assert!(validate_url("http://anvil:8545").is_ok());
+        assert!(validate_url("http://127.0.0.1:8545").is_ok());
+        assert!(validate_url("").is_ok());
 
         // Invalid URLs
-        assert!(validate_url(&"invalid-url".to_string()).is_err());
-        assert!(validate_url(&"ftp://example.com".to_string()).is_err());
-        assert!(validate_url(&"http://:8545".to_string()).is_err());
-        assert!(validate_url(&"http://invalid@host:8545".to_string()).is_err());
+        assert!(validate_url("invalid-url").is_err());
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("http://:8545").is_err());
+        assert!(validate_url("http://invalid@host:8545").is_err());
     }
 
     #[test]
@@ -301,98 +373,146 @@ mod tests {
     #[test]
     fn test_validate_eth_address() {
         // Valid addresses
-        assert!(validate_eth_address(&"".to_string()).is_ok());
-        assert!(
-            validate_eth_address(&"0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()).is_ok()
-        );
-        assert!(
-            validate_eth_address(&"0x0000000000000000000000000000000000000000".to_string()).is_ok()
-        );
+        assert!(validate_eth_address("").is_ok());
+        assert!(validate_eth_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44e").is_ok());
+        assert!(validate_eth_address("0x0000000000000000000000000000000000000000").is_ok());
 
         // Invalid addresses
-        assert!(
-            validate_eth_address(&"742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()).is_err()
-        ); // Missing 0x
-        assert!(
-            validate_eth_address(&"0x742d35Cc6634C0532925a3b844Bc454e4438f44".to_string()).is_err()
-        ); // Too short
-        assert!(
-            validate_eth_address(&"0x742d35Cc6634C0532925a3b844Bc454e4438f44ef".to_string())
-                .is_err()
-        ); // Too long
-        assert!(
-            validate_eth_address(&"0x742d35Cc6634C0532925a3b844Bc454e4438f44g".to_string())
-                .is_err()
-        ); // Invalid hex
+        assert!(validate_eth_address("742d35Cc6634C0532925a3b844Bc454e4438f44e").is_err()); // Missing 0x
+        assert!(validate_eth_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44").is_err()); // Too short
+        assert!(validate_eth_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44ef").is_err()); // Too long
+        assert!(validate_eth_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44g").is_err());
+        // Invalid hex
     }
 
     #[test]
     fn test_validate_private_key() {
         // Valid private keys
-        assert!(validate_private_key(&"".to_string()).is_ok());
+        assert!(validate_private_key("").is_ok());
         assert!(validate_private_key(
-            &"0x0000000000000000000000000000000000000000000000000000000000000001".to_string()
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
         )
         .is_ok());
         assert!(validate_private_key(
-            &"0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141".to_string()
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
         )
         .is_ok());
 
         // Invalid private keys
         assert!(validate_private_key(
-            &"0000000000000000000000000000000000000000000000000000000000000001".to_string()
+            "0000000000000000000000000000000000000000000000000000000000000001"
         )
         .is_err()); // Missing 0x
         assert!(validate_private_key(
-            &"0x00000000000000000000000000000000000000000000000000000000000001".to_string()
+            "0x00000000000000000000000000000000000000000000000000000000000001"
         )
         .is_err()); // Too short
         assert!(validate_private_key(
-            &"0x000000000000000000000000000000000000000000000000000000000000001".to_string()
+            "0x000000000000000000000000000000000000000000000000000000000000001"
         )
         .is_err()); // Too long
         assert!(validate_private_key(
-            &"0x000000000000000000000000000000000000000000000000000000000000000g".to_string()
+            "0x000000000000000000000000000000000000000000000000000000000000000g"
         )
         .is_err()); // Invalid hex
     }
 
+    #[test]
+    fn test_validate_starknet_address() {
+        // Valid addresses
+        assert!(validate_starknet_address("").is_ok());
+        assert!(validate_starknet_address(
+            "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
+        )
+        .is_ok());
+        assert!(validate_starknet_address("0x1").is_ok());
+
+        // Invalid addresses
+        assert!(validate_starknet_address("0x").is_err()); // No digits
+        assert!(validate_starknet_address("1234").is_err()); // Missing 0x
+        assert!(validate_starknet_address("0x12g4").is_err()); // Invalid hex
+        assert!(validate_starknet_address(&format!("0x{}", "1".repeat(65))).is_err());
+        // Too long
+        assert!(validate_starknet_address(&format!("0x{}", "f".repeat(64))).is_err());
+        // Out of the field
+    }
+
+    #[test]
+    fn test_validate_felt() {
+        // Valid felts
+        assert!(validate_felt("").is_ok());
+        assert!(validate_felt("0xabcd").is_ok());
+        assert!(
+            validate_felt("0x41fc2a467ef8649580631912517edcab7674173f1dbfa2e9b64fbcd82bc4d79")
+                .is_ok()
+        );
+        assert!(validate_felt(
+            "0x0800000000000011000000000000000000000000000000000000000000000000"
+        )
+        .is_ok()); // Prime - 1
+
+        // Invalid felts
+        assert!(validate_felt("abcd").is_err()); // Missing 0x
+        assert!(validate_felt("0xabcg").is_err()); // Invalid hex
+        assert!(
+            validate_felt("0x800000000000011000000000000000000000000000000000000000000000001")
+                .is_err()
+        ); // Prime
+        assert!(validate_felt(
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+        )
+        .is_err()); // Ethereum key out of the field
+    }
+
+    #[test]
+    fn test_validate_version() {
+        // Valid versions
+        assert!(validate_version("").is_ok());
+        assert!(validate_version("0.13.2").is_ok());
+        assert!(validate_version("0.13.2.1").is_ok());
+
+        // Invalid versions
+        assert!(validate_version("0.13").is_err()); // Too short
+        assert!(validate_version("0.13.2.1.0").is_err()); // Too long
+        assert!(validate_version("v0.13.2").is_err()); // Prefix
+        assert!(validate_version("0..2").is_err()); // Empty part
+    }
+
     #[test]
     fn test_validate_u64() {
         // Valid values
-        assert!(validate_u64(&"".to_string()).is_ok());
-        assert!(validate_u64(&"0".to_string()).is_ok());
-        assert!(validate_u64(&"42".to_string()).is_ok());
-        assert!(validate_u64(&"18446744073709551615".to_string()).is_ok()); // max u64
+        assert!(validate_u64("").is_ok());
+        assert!(validate_u64("0").is_ok());
+        assert!(validate_u64("42").is_ok());
+        assert!(validate_u64("18446744073709551615").is_ok()); // max u64
 
         // Invalid values
-        assert!(validate_u64(&"18446744073709551616".to_string()).is_err()); // > max u64
-        assert!(validate_u64(&"-1".to_string()).is_err()); // negative
-        assert!(validate_u64(&"3.14".to_string()).is_err()); // float
-        assert!(validate_u64(&"abc".to_string()).is_err()); // non-numeric
-        assert!(validate_u64(&"123abc".to_string()).is_err()); // mixed
+        assert!(validate_u64("18446744073709551616").is_err()); // > max u64
+        assert!(validate_u64("-1").is_err()); // negative
+        assert!(validate_u64("3.14").is_err()); // float
+        assert!(validate_u64("abc").is_err()); // non-numeric
+        assert!(validate_u64("123abc").is_err()); // mixed
     }
 
     #[test]
     fn test_validate_time_with_unit() {
         // Valid cases
-        assert!(validate_time_with_unit(&"".to_string()).is_ok());
-        assert!(validate_time_with_unit(&"15s".to_string()).is_ok());
-        assert!(validate_time_with_unit(&"1s".to_string()).is_ok());
-        assert!(validate_time_with_unit(&"1.5s".to_string()).is_ok());
-        assert!(validate_time_with_unit(&"1.05s".to_string()).is_ok());
-        assert!(validate_time_with_unit(&"100.00s".to_string()).is_ok());
+        assert!(validate_time_with_unit("").is_ok());
+        assert!(validate_time_with_unit("15s").is_ok());
+        assert!(validate_time_with_unit("1s").is_ok());
+        assert!(validate_time_with_unit("1.5s").is_ok());
+        assert!(validate_time_with_unit("1.05s").is_ok());
+        assert!(validate_time_with_unit("100.00s").is_ok());
 
         // Invalid cases
-        assert!(validate_time_with_unit(&"0s".to_string()).is_err()); // Zero
-        assert!(validate_time_with_unit(&"-1s".to_string()).is_err()); // Negative
-        assert!(validate_time_with_unit(&"15".to_string()).is_err()); // Missing unit
-        assert!(validate_time_with_unit(&"1.234s".to_string()).is_err()); // Too many decimals
-        assert!(validate_time_with_unit(&"1,5s".to_string()).is_err()); // Wrong decimal separator
-        assert!(validate_time_with_unit(&"abc".to_string()).is_err()); // Invalid format
-        assert!(validate_time_with_unit(&"15m".to_string()).is_err()); // Wrong unit
-        assert!(validate_time_with_unit(&"s".to_string()).is_err()); // Only unit
-        assert!(validate_time_with_unit(&".5s".to_string()).is_err()); // Missing leading zero
+        assert!(validate_time_with_unit("0s").is_err()); // Zero
+        assert!(validate_time_with_unit("-1s").is_err()); // Negative
+        assert!(validate_time_with_unit("15").is_err()); // Missing unit
+        assert!(validate_time_with_unit("1.234s").is_err()); // Too many decimals
+        assert!(validate_time_with_unit("1,5s").is_err()); // Wrong decimal separator
+        assert!(validate_time_with_unit("abc").is_err()); // Invalid format
+        assert!(validate_time_with_unit("15m").is_err()); // Wrong unit
+        assert!(validate_time_with_unit("s").is_err()); // Only unit
+        assert!(validate_time_with_unit(".5s").is_err()); // Missing leading zero
     }
 }
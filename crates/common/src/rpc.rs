@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum RpcError {
+    /// The node could not be reached or didn't answer with a JSON-RPC response
+    #[error("Request to {url} failed: {reason}")]
+    Transport { url: String, reason: String },
+    /// The node answered with a JSON-RPC error
+    #[error("{method} returned error {code}: {message}")]
+    Rpc {
+        method: String,
+        code: i64,
+        message: String,
+    },
+    /// The node answered with an unexpected result
+    #[error("Invalid response to {method}: {reason}")]
+    InvalidResponse { method: String, reason: String },
+}
+
+pub type RpcResult<T> = Result<T, RpcError>;
+
+/// Result of `starknet_syncing`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncStatus {
+    NotSyncing,
+    Syncing {
+        starting_block_num: u64,
+        current_block_num: u64,
+        highest_block_num: u64,
+    },
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<RpcErrorObject>,
+}
+
+#[derive(Deserialize)]
+struct RpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct SyncingResult {
+    starting_block_num: u64,
+    current_block_num: u64,
+    highest_block_num: u64,
+}
+
+/// A minimal Starknet JSON-RPC client, used to check that the nodes started by the CLI
+/// (Madara, Pathfinder) are up and producing or syncing blocks.
+pub struct StarknetRpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl StarknetRpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(RPC_REQUEST_TIMEOUT)
+                .build(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of the latest accepted block
+    pub fn block_number(&self) -> RpcResult<u64> {
+        self.call("starknet_blockNumber")
+    }
+
+    /// Chain id as a hex encoded felt, see [`decode_short_string`] to get a readable value
+    pub fn chain_id(&self) -> RpcResult<String> {
+        self.call("starknet_chainId")
+    }
+
+    pub fn syncing(&self) -> RpcResult<SyncStatus> {
+        let method = "starknet_syncing";
+        match self.call::<Value>(method)? {
+            Value::Bool(false) => Ok(SyncStatus::NotSyncing),
+            value => {
+                let status: SyncingResult =
+                    serde_json::from_value(value).map_err(|e| RpcError::InvalidResponse {
+                        method: method.to_string(),
+                        reason: e.to_string(),
+                    })?;
+                Ok(SyncStatus::Syncing {
+                    starting_block_num: status.starting_block_num,
+                    current_block_num: status.current_block_num,
+                    highest_block_num: status.highest_block_num,
+                })
+            }
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str) -> RpcResult<T> {
+        let transport_error = |reason: String| RpcError::Transport {
+            url: self.url.clone(),
+            reason,
+        };
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": [],
+            "id": 1,
+        });
+
+        // JSON-RPC errors may come with a non-2xx status, the body is parsed anyway
+        let response = match self.agent.post(&self.url).send_json(request) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(transport_error(e.to_string())),
+        };
+        let response: RpcResponse = response
+            .into_json()
+            .map_err(|e| transport_error(e.to_string()))?;
+
+        if let Some(error) = response.error {
+            return Err(RpcError::Rpc {
+                method: method.to_string(),
+                code: error.code,
+                message: error.message,
+            });
+        }
+
+        serde_json::from_value(response.result.unwrap_or(Value::Null)).map_err(|e| {
+            RpcError::InvalidResponse {
+                method: method.to_string(),
+                reason: e.to_string(),
+            }
+        })
+    }
+}
+
+/// Decode a Cairo short string encoded as a hex felt, e.g. a chain id such as
+/// `0x4d41444152415f4445564e4554` (`MADARA_DEVNET`)
+pub fn decode_short_string(felt: &str) -> Option<String> {
+    let hex = felt.trim_start_matches("0x");
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = if hex.len() % 2 == 1 {
+        format!("0{}", hex)
+    } else {
+        hex.to_string()
+    };
+
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    let bytes = bytes.into_iter().skip_while(|b| *b == 0).collect();
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    /// Serve `responses` in order, one per connection, and return the server URL
+    fn mock_server(responses: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for body in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // Read the request headers and body so the client doesn't get a reset
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut request = vec![0; content_length];
+                reader.read_exact(&mut request).unwrap();
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn test_block_number() {
+        let url = mock_server(vec![r#"{"jsonrpc":"2.0","result":42,"id":1}"#]);
+        let client = StarknetRpcClient::new(&url);
+
+        assert_eq!(client.block_number().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_chain_id() {
+        let url = mock_server(vec![
+            r#"{"jsonrpc":"2.0","result":"0x4d41444152415f4445564e4554","id":1}"#,
+        ]);
+        let client = StarknetRpcClient::new(&url);
+
+        let chain_id = client.chain_id().unwrap();
+        assert_eq!(
+            decode_short_string(&chain_id).as_deref(),
+            Some("MADARA_DEVNET")
+        );
+    }
+
+    #[test]
+    fn test_syncing() {
+        let url = mock_server(vec![
+            r#"{"jsonrpc":"2.0","result":false,"id":1}"#,
+            r#"{"jsonrpc":"2.0","result":{"starting_block_hash":"0x1","starting_block_num":0,"current_block_hash":"0x2","current_block_num":10,"highest_block_hash":"0x3","highest_block_num":20},"id":1}"#,
+        ]);
+        let client = StarknetRpcClient::new(&url);
+
+        assert_eq!(client.syncing().unwrap(), SyncStatus::NotSyncing);
+        assert_eq!(
+            client.syncing().unwrap(),
+            SyncStatus::Syncing {
+                starting_block_num: 0,
+                current_block_num: 10,
+                highest_block_num: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rpc_error() {
+        let url = mock_server(vec![
+            r#"{"jsonrpc":"2.0","error":{"code":32,"message":"There are no blocks"},"id":1}"#,
+        ]);
+        let client = StarknetRpcClient::new(&url);
+
+        assert!(matches!(
+            client.block_number(),
+            Err(RpcError::Rpc { code: 32, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unreachable_node() {
+        // Bind and drop a listener to get a port that nothing listens on
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = StarknetRpcClient::new(&format!("http://127.0.0.1:{}", port));
+
+        assert!(matches!(
+            client.block_number(),
+            Err(RpcError::Transport { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_short_string() {
+        assert_eq!(
+            decode_short_string("0x534e5f5345504f4c4941").as_deref(),
+            Some("SN_SEPOLIA")
+        );
+        assert_eq!(decode_short_string("0x0").as_deref(), Some(""));
+        assert_eq!(decode_short_string("0xzz"), None);
+        assert_eq!(decode_short_string("0x4é"), None);
+    }
+}
@@ -0,0 +1,218 @@
+use std::env;
+
+use anyhow::bail;
+use clap::ValueEnum;
+use once_cell::sync::OnceCell;
+use url::Url;
+use xshell::{cmd, Shell};
+
+use crate::{
+    cmd::Cmd,
+    config::global_config,
+    docker::{ComposeContainer, Docker, LogsOptions},
+    podman::Podman,
+};
+
+static RUNTIME: OnceCell<Box<dyn ContainerRuntime>> = OnceCell::new();
+
+/// Container runtime used for the deployments, given with `--runtime` or detected on first use
+pub fn runtime() -> &'static dyn ContainerRuntime {
+    RUNTIME
+        .get_or_init(|| {
+            let kind = global_config().runtime.unwrap_or_else(RuntimeKind::detect);
+            kind.runtime()
+        })
+        .as_ref()
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, strum::Display)]
+pub enum RuntimeKind {
+    Docker,
+    /// Rootless Podman, with `podman compose` or `podman-compose`
+    Podman,
+}
+
+impl RuntimeKind {
+    /// Docker when it's installed, Podman otherwise
+    pub fn detect() -> Self {
+        if !on_path("docker") && on_path("podman") {
+            Self::Podman
+        } else {
+            Self::Docker
+        }
+    }
+
+    fn runtime(self) -> Box<dyn ContainerRuntime> {
+        match self {
+            Self::Docker => Box::new(Docker),
+            Self::Podman => Box::new(Podman::detect()),
+        }
+    }
+}
+
+/// The commands run against a container runtime and its compose implementation. Everything is
+/// built on top of [`ContainerRuntime::binary`] and [`ContainerRuntime::compose`], runtimes
+/// only override what they do differently.
+pub trait ContainerRuntime: Send + Sync {
+    fn kind(&self) -> RuntimeKind;
+
+    /// Binary of the runtime, e.g. `docker`
+    fn binary(&self) -> &'static str;
+
+    /// Compose command for the given compose file, subcommands are appended to it
+    fn compose<'a>(&self, shell: &'a Shell, compose_file: &str) -> xshell::Cmd<'a>;
+
+    /// Hostname the containers use to reach the host
+    fn host_gateway(&self) -> &'static str;
+
+    fn up(&self, shell: &Shell, compose_file: &str, detach: bool) -> anyhow::Result<()> {
+        let args = if detach { vec!["-d"] } else { vec![] };
+        let mut cmd = Cmd::new(self.compose(shell, compose_file).arg("up").args(args));
+        cmd = if !detach { cmd.with_force_run() } else { cmd };
+        Ok(cmd.run()?)
+    }
+
+    /// Start some services in the background, without their dependencies, so one-shot jobs
+    /// that already completed aren't run again
+    fn start_services(
+        &self,
+        shell: &Shell,
+        compose_file: &str,
+        services: &[&str],
+    ) -> anyhow::Result<()> {
+        let cmd = self
+            .compose(shell, compose_file)
+            .args(["up", "-d", "--no-deps"])
+            .args(services);
+        Ok(Cmd::new(cmd).run()?)
+    }
+
+    fn down(&self, shell: &Shell, compose_file: &str, remove_volumes: bool) -> anyhow::Result<()> {
+        let args = if remove_volumes { vec!["-v"] } else { vec![] };
+        Ok(Cmd::new(self.compose(shell, compose_file).arg("down").args(args)).run()?)
+    }
+
+    /// Stream the logs of a compose project, calling `on_line` with the source of every line
+    /// (as printed by compose) and its message
+    fn logs(
+        &self,
+        shell: &Shell,
+        compose_file: &str,
+        options: &LogsOptions,
+        on_line: &mut dyn FnMut(&str, &str),
+    ) -> anyhow::Result<()> {
+        let mut args = vec!["--no-color".to_string()];
+        if options.follow {
+            args.push("--follow".to_string());
+        }
+        if let Some(since) = &options.since {
+            args.extend(["--since".to_string(), since.clone()]);
+        }
+        if let Some(tail) = options.tail {
+            args.extend(["--tail".to_string(), tail.to_string()]);
+        }
+
+        let cmd = self
+            .compose(shell, compose_file)
+            .arg("logs")
+            .args(args)
+            .args(&options.services);
+        Ok(
+            Cmd::new(cmd).run_with_line_handler(|line| match line.split_once(" | ") {
+                Some((source, message)) => on_line(source, message),
+                None => on_line("", line),
+            })?,
+        )
+    }
+
+    /// List all the containers (running or not) of a compose project
+    fn ps(&self, shell: &Shell, compose_file: &str) -> anyhow::Result<Vec<ComposeContainer>> {
+        let cmd = self
+            .compose(shell, compose_file)
+            .args(["ps", "--all", "--format", "json"]);
+        let output = Cmd::new(cmd).run_with_output()?;
+
+        if !output.status.success() {
+            bail!(
+                "Failed to query services from {}: {}",
+                compose_file,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        self.parse_ps_output(&String::from_utf8(output.stdout)?)
+    }
+
+    /// Parse the output of `ps --format json`
+    fn parse_ps_output(&self, output: &str) -> anyhow::Result<Vec<ComposeContainer>>;
+
+    fn run(&self, shell: &Shell, image: &str, args: Vec<String>) -> anyhow::Result<()> {
+        let binary = self.binary();
+        Ok(Cmd::new(cmd!(shell, "{binary} run {args...} {image}")).run()?)
+    }
+
+    fn run_command(
+        &self,
+        shell: &Shell,
+        image: &str,
+        args: Vec<String>,
+        command: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let binary = self.binary();
+        Ok(Cmd::new(cmd!(shell, "{binary} run {args...} {image} {command...}")).run()?)
+    }
+
+    fn exec_in_container(
+        &self,
+        shell: &Shell,
+        container_name: &str,
+        command: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let binary = self.binary();
+        Ok(Cmd::new(cmd!(shell, "{binary} exec {container_name} {command...}")).run()?)
+    }
+
+    fn build_image(&self, shell: &Shell, path: String, name: String) -> anyhow::Result<()> {
+        let binary = self.binary();
+        Ok(Cmd::new(cmd!(shell, "{binary} build -t {name} {path}")).run()?)
+    }
+
+    /// Version of the runtime client, even when the daemon can't be reached
+    fn client_version(&self, shell: &Shell) -> anyhow::Result<String>;
+
+    /// Version of the daemon (or service) running the containers, failing when it can't be
+    /// reached
+    fn server_version(&self, shell: &Shell) -> anyhow::Result<String>;
+
+    /// Version of the compose implementation
+    fn compose_version(&self, shell: &Shell) -> anyhow::Result<String>;
+
+    /// Point URLs to the host at the address the containers use for it
+    fn adjust_localhost(&self, mut url: Url) -> anyhow::Result<Url> {
+        match url.host_str() {
+            Some("localhost" | "127.0.0.1") => url.set_host(Some(self.host_gateway()))?,
+            Some(_) => {}
+            None => bail!("Failed to parse: no host"),
+        }
+        Ok(url)
+    }
+}
+
+/// Run a version command, failing with the first line of its error output
+pub(crate) fn version_output(mut cmd: Cmd) -> anyhow::Result<String> {
+    let output = cmd.run_with_output()?;
+    if !output.status.success() {
+        bail!("{}", first_line(&output.stderr));
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+pub(crate) fn first_line(output: &[u8]) -> String {
+    let output = String::from_utf8_lossy(output);
+    output.lines().next().unwrap_or_default().trim().to_string()
+}
+
+pub(crate) fn on_path(binary: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+}
@@ -1,61 +1,150 @@
 use std::fmt::Display;
 
 use cliclack::{intro as cliclak_intro, log, outro as cliclak_outro, Theme, ThemeState};
-use console::{style, Emoji, Term};
+use console::{style, Color, Emoji, Term};
 use serde::Serialize;
 
-use crate::prompt::CliclackTheme;
+use crate::{
+    output::{self, plain, Event, Level},
+    prompt::CliclackTheme,
+};
 
 const S_BAR: Emoji = Emoji("│", "|");
 
+const PREFIX_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+];
+
 fn term_write(msg: impl Display) {
     let msg = &format!("{}", msg);
     Term::stderr().write_str(msg).unwrap();
 }
 
+/// With `--output json`, log messages are printed as [`Event::Log`]
+fn emit_log(level: Level, msg: impl Display) -> bool {
+    if !output::is_json() {
+        return false;
+    }
+    output::emit(Event::Log {
+        level,
+        message: plain(msg),
+    });
+    true
+}
+
 pub fn intro(msg: impl Display) {
+    if output::is_json() {
+        return output::emit(Event::Intro {
+            message: plain(msg),
+        });
+    }
     cliclak_intro(style(msg).on_cyan().black()).unwrap();
 }
 
 pub fn outro(msg: impl Display) {
+    if output::is_json() {
+        return output::emit(Event::Outro {
+            message: plain(msg),
+        });
+    }
     cliclak_outro(msg).unwrap();
 }
 
 pub fn info(msg: impl Display) {
+    if emit_log(Level::Info, &msg) {
+        return;
+    }
     log::info(msg).unwrap();
 }
 
 pub fn debug(msg: impl Display) {
+    if emit_log(Level::Debug, &msg) {
+        return;
+    }
     let msg = &format!("{}", msg);
     let log = CliclackTheme.format_log(msg, style("⚙").dim().to_string().as_str());
     Term::stderr().write_str(&log).unwrap();
 }
 
 pub fn warn(msg: impl Display) {
+    if emit_log(Level::Warn, &msg) {
+        return;
+    }
     log::warning(msg).unwrap();
 }
 
 pub fn error(msg: impl Display) {
+    if emit_log(Level::Error, &msg) {
+        return;
+    }
     log::error(style(msg).red()).unwrap();
 }
 
 pub fn success(msg: impl Display) {
+    if emit_log(Level::Success, &msg) {
+        return;
+    }
     log::success(msg).unwrap();
 }
 
 pub fn step(msg: impl Display) {
+    if emit_log(Level::Step, &msg) {
+        return;
+    }
     log::step(msg).unwrap();
 }
 
+/// Write to stderr as is, e.g. the output of a command. Dropped with `--output json`, since
+/// it's already in the events that need it.
 pub fn raw(msg: impl Display) {
+    if output::is_json() {
+        return;
+    }
     term_write(msg);
 }
 
+/// Print a line to stdout prefixed by a label, e.g. a service name. Each label always gets
+/// the same color so interleaved sources are easy to tell apart.
+pub fn prefixed(prefix: &str, msg: impl Display) {
+    if output::is_json() {
+        return output::emit(Event::LogLine {
+            source: prefix.trim().to_string(),
+            message: plain(msg),
+        });
+    }
+    let index = prefix.trim().bytes().fold(0usize, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    let color = PREFIX_COLORS[index % PREFIX_COLORS.len()];
+    let bar = CliclackTheme.bar_color(&ThemeState::Submit).apply_to(S_BAR);
+
+    output::print_line(format!(
+        "{} {} {}",
+        bar,
+        style(prefix).fg(color).bold(),
+        msg
+    ));
+}
+
 pub fn note(msg: impl Display, content: impl Display) {
+    if output::is_json() {
+        return output::emit(Event::Note {
+            title: plain(msg),
+            content: plain(content),
+        });
+    }
     cliclack::note(msg, content).unwrap();
 }
 
 pub fn error_note(msg: &str, content: &str) {
+    if emit_log(Level::Error, format!("{}\n{}", msg, content)) {
+        return;
+    }
     let note = CliclackTheme.format_log(msg, &CliclackTheme.error_symbol());
     term_write(note);
     let note = CliclackTheme.format_log(content, &CliclackTheme.error_symbol());
@@ -106,11 +195,11 @@ pub fn object_to_string(obj: impl Serialize) -> String {
 }
 
 pub fn new_empty_line() {
-    term_write("\n");
+    raw("\n");
 }
 
 pub fn new_line() {
-    term_write(format!(
+    raw(format!(
         "{}\n",
         CliclackTheme.bar_color(&ThemeState::Submit).apply_to(S_BAR)
     ))
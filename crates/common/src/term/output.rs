@@ -0,0 +1,160 @@
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Write},
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{cmd::CmdError, config::try_global_config};
+
+/// How the CLI reports what it does, chosen with `--output`
+#[derive(Debug, Clone, Copy, Default, ValueEnum, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum OutputFormat {
+    /// Interactive rendering for a terminal
+    #[default]
+    Text,
+    /// One JSON event per line on stdout, for pipelines and bots
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Step,
+    Success,
+    Warn,
+    Error,
+}
+
+/// An event printed with `--output json`, tagged by its `event` field
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Intro {
+        message: String,
+    },
+    Outro {
+        message: String,
+    },
+    Log {
+        level: Level,
+        message: String,
+    },
+    Note {
+        title: String,
+        content: String,
+    },
+    StepStarted {
+        message: String,
+    },
+    StepFinished {
+        message: String,
+        duration_ms: u128,
+    },
+    StepFailed {
+        message: String,
+        duration_ms: u128,
+    },
+    /// A line printed by a service, e.g. with `logs`
+    LogLine {
+        source: String,
+        message: String,
+    },
+    /// Files written into a deployment directory
+    FilesRendered {
+        deployment: String,
+        files: Vec<String>,
+    },
+    /// Rows of a table, e.g. the endpoints or the deployments
+    Table {
+        name: String,
+        rows: Value,
+    },
+    /// The content of a file, e.g. with `render --print` or `config show`
+    Document {
+        name: String,
+        content: String,
+    },
+    Error {
+        message: String,
+        causes: Vec<String>,
+        /// Error output of the command that failed, if any
+        stderr: Option<String>,
+    },
+}
+
+/// Whether the events are printed as JSON. Before the global configuration is initialized,
+/// e.g. when the workspace can't be set up, the output is text.
+pub fn is_json() -> bool {
+    try_global_config().is_some_and(|config| config.output == OutputFormat::Json)
+}
+
+/// Print an event as a line of JSON on stdout
+pub fn emit(event: Event) {
+    let line = serde_json::to_string(&event).expect("Events are always serializable");
+    print_line(line);
+}
+
+/// Print a line on stdout. When stdout is closed, e.g. piped into `head`, nothing more can be
+/// printed and the CLI stops quietly where `println!` would panic.
+pub(crate) fn print_line(line: impl Display) {
+    let mut stdout = std::io::stdout().lock();
+    if let Err(e) = writeln!(stdout, "{}", line).and_then(|_| stdout.flush()) {
+        if e.kind() == ErrorKind::BrokenPipe {
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Print rows as a [`Event::Table`]
+pub fn emit_table(name: &str, rows: impl Serialize) -> anyhow::Result<()> {
+    emit(Event::Table {
+        name: name.to_string(),
+        rows: serde_json::to_value(rows)?,
+    });
+    Ok(())
+}
+
+/// Print an error as an [`Event::Error`], with the error output of the failed command
+pub fn emit_error(error: &anyhow::Error) {
+    let stderr = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<CmdError>())
+        .and_then(|cmd_error| cmd_error.stderr.clone());
+    emit(Event::Error {
+        message: plain(error),
+        causes: error.chain().skip(1).map(plain).collect(),
+        stderr,
+    });
+}
+
+/// Messages are styled for the terminal, JSON events only keep their text
+pub(crate) fn plain(message: impl std::fmt::Display) -> String {
+    console::strip_ansi_codes(&message.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = Event::StepFinished {
+            message: plain(
+                console::style("Starting services...")
+                    .bold()
+                    .force_styling(true),
+            ),
+            duration_ms: 1500,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"step_finished","message":"Starting services...","duration_ms":1500}"#
+        );
+    }
+}
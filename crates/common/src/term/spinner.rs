@@ -2,7 +2,11 @@ use std::{fmt::Display, io::IsTerminal, time::Instant};
 
 use cliclack::{spinner, ProgressBar};
 
-use crate::{config::global_config, logger};
+use crate::{
+    config::global_config,
+    logger,
+    output::{self, Event},
+};
 
 /// Spinner is a helper struct to show a spinner while some operation is running.
 pub struct Spinner {
@@ -14,7 +18,12 @@ pub struct Spinner {
 impl Spinner {
     /// Create a new spinner with a message.
     pub fn new(msg: &str) -> Self {
-        let output = if std::io::stdout().is_terminal() {
+        let output = if output::is_json() {
+            output::emit(Event::StepStarted {
+                message: msg.to_owned(),
+            });
+            SpinnerOutput::Json
+        } else if std::io::stdout().is_terminal() {
             let pb = spinner();
             pb.start(msg);
             if global_config().verbose {
@@ -34,8 +43,14 @@ impl Spinner {
 
     /// Manually finish the spinner.
     pub fn finish(self) {
+        if let SpinnerOutput::Json = self.output {
+            return output::emit(Event::StepFinished {
+                message: self.msg,
+                duration_ms: self.time.elapsed().as_millis(),
+            });
+        }
         self.output.stop(format!(
-            "{} done in {} secs",
+            "{} done in {:.1}s",
             self.msg,
             self.time.elapsed().as_secs_f64()
         ));
@@ -43,8 +58,14 @@ impl Spinner {
 
     /// Interrupt the spinner with a failed message.
     pub fn fail(self) {
+        if let SpinnerOutput::Json = self.output {
+            return output::emit(Event::StepFailed {
+                message: self.msg,
+                duration_ms: self.time.elapsed().as_millis(),
+            });
+        }
         self.output.error(format!(
-            "{} failed in {} secs",
+            "{} failed in {:.1}s",
             self.msg,
             self.time.elapsed().as_secs_f64()
         ));
@@ -52,6 +73,12 @@ impl Spinner {
 
     /// Freeze the spinner with current message.
     pub fn freeze(self) {
+        if let SpinnerOutput::Json = self.output {
+            return output::emit(Event::StepFinished {
+                message: self.msg,
+                duration_ms: self.time.elapsed().as_millis(),
+            });
+        }
         self.output.stop(self.msg);
     }
 }
@@ -59,26 +86,27 @@ impl Spinner {
 /// An abstraction that makes interactive progress bar optional in environments where virtual
 /// terminal is not available.
 ///
-/// Uses plain `logger::{info,error}` as the fallback.
+/// Uses plain `logger::{info,error}` as the fallback, and step events with `--output json`.
 ///
 /// See https://github.com/console-rs/indicatif/issues/530 for more details.
 enum SpinnerOutput {
     Progress(ProgressBar),
     Plain(),
+    Json,
 }
 
 impl SpinnerOutput {
     fn error(&self, msg: impl Display) {
         match self {
             SpinnerOutput::Progress(pb) => pb.error(msg),
-            SpinnerOutput::Plain() => logger::error(msg),
+            SpinnerOutput::Plain() | SpinnerOutput::Json => logger::error(msg),
         }
     }
 
     fn stop(self, msg: impl Display) {
         match self {
             SpinnerOutput::Progress(pb) => pb.stop(msg),
-            SpinnerOutput::Plain() => logger::info(msg),
+            SpinnerOutput::Plain() | SpinnerOutput::Json => logger::info(msg),
         }
     }
 }
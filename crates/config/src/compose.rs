@@ -1,18 +1,38 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::BTreeMap, fs};
 
 /// Represents the entire configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Compose {
+    /// Optional project name, prefixing the networks and volumes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     /// A map of service names to their configurations.
-    pub services: HashMap<String, Service>,
+    pub services: BTreeMap<String, Service>,
 
     /// A map of secret names to their configurations.
-    pub secrets: HashMap<String, Secret>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub secrets: BTreeMap<String, Secret>,
+}
+
+impl Compose {
+    /// Load a compose file from disk
+    pub fn load(file_path: &str) -> anyhow::Result<Compose> {
+        let content = fs::read_to_string(file_path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
+    /// Write the compose file to disk
+    pub fn save(&self, file_path: &str) -> anyhow::Result<()> {
+        fs::write(file_path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
 }
 
 /// Represents a single service configuration.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Service {
     /// The Docker image to use for the service.
     pub image: String,
@@ -39,9 +59,13 @@ pub struct Service {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub labels: Option<Vec<String>>,
 
-    /// Optional list of environment variables.
+    /// Optional working directory inside the container.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub environment: Option<Vec<String>>,
+    pub working_dir: Option<String>,
+
+    /// Optional environment variables, either as a list or a map.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
 
     /// Optional list of secrets.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,7 +77,15 @@ pub struct Service {
 
     /// Optional entrypoint command.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub entrypoint: Option<Vec<String>>,
+    pub entrypoint: Option<CommandLine>,
+
+    /// Optional command, passed as arguments to the entrypoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<CommandLine>,
+
+    /// Optional map of the services to wait for before starting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<BTreeMap<String, Dependency>>,
 
     /// Optional healthcheck configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -66,25 +98,65 @@ pub struct Service {
     /// Optional TTY allocation (useful for services like `autoheal`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tty: Option<bool>,
+
+    /// Optional flag to hide the logs of the service from `docker compose up`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<bool>,
+}
+
+/// Represents a service dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// The state the dependency must reach.
+    pub condition: DependencyCondition,
+}
+
+/// Represents the state a dependency must reach before the service starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyCondition {
+    ServiceStarted,
+    ServiceHealthy,
+    ServiceCompletedSuccessfully,
 }
 
 /// Represents the healthcheck configuration for a service.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Healthcheck {
     /// The command to run for the healthcheck.
     pub test: Vec<String>,
 
     /// The interval between healthchecks (e.g., "10s").
-    pub interval: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
 
     /// The timeout for each healthcheck (e.g., "5s").
-    pub timeout: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
 
     /// The number of retries before marking the service as unhealthy.
-    pub retries: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
 
     /// The startup period before starting healthchecks (e.g., "10s").
-    pub start_period: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+}
+
+/// A command written either as a single string or as a list of arguments.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CommandLine {
+    String(String),
+    List(Vec<String>),
+}
+
+/// Environment variables written either as a list (`KEY=value`) or as a map.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Environment {
+    List(Vec<String>),
+    Map(BTreeMap<String, String>),
 }
 
 /// Represents a single secret configuration.
@@ -93,3 +165,100 @@ pub struct Secret {
     /// The file path to the secret.
     pub file: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_madara_compose() {
+        // Template expressions are quoted, so the template is valid YAML
+        let compose: Compose =
+            serde_yaml::from_str(include_str!("../../../deps/madara/compose.template")).unwrap();
+
+        let madara = &compose.services["madara"];
+        assert_eq!(
+            madara.container_name.as_deref(),
+            Some("{{ PROJECT_NAME }}-madara")
+        );
+        assert_eq!(madara.healthcheck.as_ref().unwrap().retries, Some(3));
+        assert!(compose.services["autoheal"].healthcheck.is_none());
+        assert_eq!(
+            compose.secrets["rpc_api_key"].file,
+            "./madara/.secrets/rpc_api.secret"
+        );
+    }
+
+    #[test]
+    fn test_parse_dependencies() {
+        let compose: Compose = serde_yaml::from_str(
+            r#"
+services:
+  orchestrator:
+    image: orchestrator:latest
+    working_dir: /app
+    command: -c "node override_state.js"
+    depends_on:
+      bootstrapper_l2:
+        condition: service_completed_successfully
+      localstack:
+        condition: service_healthy
+    attach: false
+"#,
+        )
+        .unwrap();
+
+        let orchestrator = &compose.services["orchestrator"];
+        let depends_on = orchestrator.depends_on.as_ref().unwrap();
+        assert_eq!(
+            depends_on["bootstrapper_l2"].condition,
+            DependencyCondition::ServiceCompletedSuccessfully
+        );
+        assert_eq!(
+            depends_on["localstack"].condition,
+            DependencyCondition::ServiceHealthy
+        );
+        assert_eq!(orchestrator.working_dir.as_deref(), Some("/app"));
+        assert!(matches!(orchestrator.command, Some(CommandLine::String(_))));
+        assert_eq!(orchestrator.attach, Some(false));
+    }
+
+    #[test]
+    fn test_parse_mixed_syntax() {
+        let compose: Compose = serde_yaml::from_str(
+            r#"
+services:
+  override_state:
+    image: helper:latest
+    entrypoint: /bin/bash
+    environment:
+      MADARA_ORCHESTRATOR_ETHEREUM_PRIVATE_KEY: 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80
+  localstack:
+    image: localstack/localstack
+    healthcheck:
+      test: ["CMD", "curl", "-f", "http://localhost:4566/_localstack/health"]
+      interval: 30s
+      timeout: 10s
+      retries: 5
+"#,
+        )
+        .unwrap();
+
+        let override_state = &compose.services["override_state"];
+        assert!(matches!(
+            override_state.entrypoint,
+            Some(CommandLine::String(_))
+        ));
+        assert!(matches!(
+            override_state.environment,
+            Some(Environment::Map(_))
+        ));
+        assert!(compose.services["localstack"]
+            .healthcheck
+            .as_ref()
+            .unwrap()
+            .start_period
+            .is_none());
+        assert!(compose.secrets.is_empty());
+    }
+}
@@ -10,7 +10,11 @@ use clap::{
 };
 use cliclack::Confirm;
 use madara_cli_common::{validation::validate_url, Prompt, PromptSelect};
-use madara_cli_types::madara::{MadaraMode, MadaraNetwork};
+use madara_cli_types::{
+    madara::{MadaraMode, MadaraNetwork},
+    resources::ResourceProfile,
+    stage::Stage,
+};
 use strum::{EnumIter, IntoEnumIterator};
 
 use crate::{
@@ -21,19 +25,18 @@ use crate::{
 /// A development environment similar to a Sequencer, but with predeployed contracts and pre-funded accounts
 #[derive(Debug, Parser, Clone)]
 pub struct MadaraRunnerConfigDevnet {
-    #[arg(short, long, default_value = "../data/devnet-db")]
+    /// Data directory, relative to the deployment directory
+    #[arg(short, long, default_value = "./data/madara")]
     pub base_path: String,
 }
 
 impl MadaraRunnerConfigDevnet {
     pub fn fill_values_with_prompt() -> anyhow::Result<MadaraRunnerConfigDevnet> {
         let base_path = Prompt::new("Input DB folder name:")
-            .default("../data/devnet-db")
+            .default("./data/madara")
             .ask();
 
-        Ok(MadaraRunnerConfigDevnet {
-            base_path: base_path,
-        })
+        Ok(MadaraRunnerConfigDevnet { base_path })
     }
 }
 
@@ -48,7 +51,8 @@ impl Default for MadaraRunnerConfigDevnet {
 /// A node that synchronizes the state and re-executes transactions to verify them
 #[derive(Debug, Default, Parser, Clone)]
 pub struct MadaraRunnerConfigFullNode {
-    #[arg(short, long, default_value = "../data/fullnode-db")]
+    /// Data directory, relative to the deployment directory
+    #[arg(short, long, default_value = "./data/madara")]
     pub base_path: String,
     #[arg(short, long)]
     pub network: MadaraNetwork,
@@ -59,13 +63,13 @@ pub struct MadaraRunnerConfigFullNode {
 impl MadaraRunnerConfigFullNode {
     pub fn fill_values_with_prompt() -> anyhow::Result<MadaraRunnerConfigFullNode> {
         let base_path = Prompt::new("Input DB folder name:")
-            .default("../data/fullnode-db")
+            .default("./data/madara")
             .ask();
 
         let network = PromptSelect::new("Select Network:", MadaraNetwork::iter()).ask();
 
         Ok(MadaraRunnerConfigFullNode {
-            base_path: base_path,
+            base_path,
             network,
             rpc_api_url: None,
         })
@@ -104,25 +108,36 @@ impl Default for MadaraRunnerParams {
     }
 }
 
-#[derive(Debug, Parser, Clone)]
+#[derive(Debug, Default, Parser, Clone)]
 pub struct MadaraRunnerConfigMode {
-    #[arg(short, long, default_value = "Madara")]
-    pub name: String,
+    /// Name of the deployment, so several deployments can coexist. Defaults to the mode,
+    /// e.g. `devnet`
+    #[arg(short, long)]
+    pub name: Option<String>,
     pub mode: Option<MadaraMode>,
+    /// Start the services in the background and wait until they are ready
+    #[arg(long, global = true)]
+    pub detach: bool,
+    /// Publish the services on free host ports when the configured ones are already in use
+    #[arg(long, global = true)]
+    pub auto_ports: bool,
+    /// Preset CPU and memory limits of the services, overriding `resources.profile`
+    #[arg(long, global = true)]
+    pub profile: Option<ResourceProfile>,
+    /// Compose file merged into the rendered one, can be repeated. Applied after the
+    /// `[compose.overrides]` section of the configuration
+    #[arg(long = "compose-override", value_name = "FILE", global = true)]
+    pub compose_overrides: Vec<String>,
+    /// Continue an app-chain deployment from its first unfinished stage
+    #[arg(long, global = true, conflicts_with = "from_stage")]
+    pub resume: bool,
+    /// Run an app-chain deployment again from the given stage, keeping the earlier ones
+    #[arg(long, global = true, value_name = "STAGE")]
+    pub from_stage: Option<Stage>,
     #[clap(subcommand)]
     pub params: MadaraRunnerParams,
 }
 
-impl Default for MadaraRunnerConfigMode {
-    fn default() -> Self {
-        Self {
-            name: String::new(),
-            mode: None,
-            params: MadaraRunnerParams::default(),
-        }
-    }
-}
-
 impl MadaraRunnerConfigMode {
     /// Retrieve MadaraMode from current configuration
     pub fn mode(&self) -> MadaraMode {
@@ -134,8 +149,26 @@ impl MadaraRunnerConfigMode {
         }
     }
 
+    /// Name of the deployment directory and compose project
+    pub fn deployment_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self
+                .mode()
+                .to_possible_value()
+                .expect("Modes are not skipped")
+                .get_name()
+                .to_string(),
+        }
+    }
+
+    /// Name given to the Madara node
+    pub fn node_name(&self) -> String {
+        self.name.clone().unwrap_or("Madara".to_string())
+    }
+
     pub fn fill_values_with_prompt() -> anyhow::Result<MadaraRunnerConfigMode> {
-        let name = "Madara".to_string();
+        let name = None;
         let mode = PromptSelect::new("Select Madara mode:", MadaraMode::iter()).ask();
 
         let params = match mode {
@@ -156,6 +189,12 @@ impl MadaraRunnerConfigMode {
         Ok(MadaraRunnerConfigMode {
             name,
             mode: Some(mode),
+            detach: false,
+            auto_ports: false,
+            profile: None,
+            compose_overrides: vec![],
+            resume: false,
+            from_stage: None,
             params,
         })
     }
@@ -164,7 +203,8 @@ impl MadaraRunnerConfigMode {
 /// A standalone node responsible for creating new blocks and ordering transactions
 #[derive(Debug, Parser, Clone)]
 pub struct MadaraRunnerConfigSequencer {
-    #[arg(long, default_value = "../data/sequencer-db")]
+    /// Data directory, relative to the deployment directory
+    #[arg(long, default_value = "./data/madara")]
     pub base_path: String,
     #[arg(long, default_value = "configs/presets/devnet.yaml")]
     pub chain_config_path: String,
@@ -175,7 +215,7 @@ pub struct MadaraRunnerConfigSequencer {
 impl MadaraRunnerConfigSequencer {
     pub fn fill_values_with_prompt() -> anyhow::Result<MadaraRunnerConfigSequencer> {
         let base_path = Prompt::new("Input DB folder name:")
-            .default("../data/sequencer-db")
+            .default("./data/madara")
             .ask();
 
         let chain_config_path = Prompt::new("Input chain config path:")
@@ -214,7 +254,9 @@ impl Default for MadaraRunnerConfigSequencer {
 /// Spin up your own Madara L2/L3 chain with complete infrastructure
 #[derive(Debug, Clone, Parser)]
 pub struct MadaraRunnerConfigAppChain {
-    #[arg(long, default_value = "configs/presets/devnet.yaml")]
+    /// Preset the configuration of the chain is applied to: sepolia, mainnet, devnet,
+    /// integration or the path of a custom preset file
+    #[arg(long, default_value = "devnet")]
     pub chain_config_path: String,
 
     #[clap(flatten)]
@@ -230,9 +272,10 @@ pub struct MadaraRunnerConfigAppChain {
 impl MadaraRunnerConfigAppChain {
     /// Fill AppChain configuration using interactive prompts
     pub fn fill_values_with_prompt() -> anyhow::Result<MadaraRunnerConfigAppChain> {
-        let chain_config_path = Prompt::new("Input chain config path:")
-            .default("configs/presets/devnet.yaml")
-            .ask();
+        let chain_config_path =
+            Prompt::new("Input chain preset (sepolia, mainnet, devnet, integration) or path:")
+                .default("devnet")
+                .ask();
 
         let prover_config = ProverRunnerConfig::fill_values_with_prompt()?;
 
@@ -0,0 +1,425 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context};
+use madara_cli_common::{logger, output};
+use madara_cli_types::stage::Stage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{
+    bootstrapper::BootstrapperFileConfiguration, madara::MadaraPresetConfiguration,
+};
+
+use super::{
+    bootstrapper::BOOTSTRAPPER_CONFIG_FILE, deployment::Deployment, madara::APPCHAIN_PRESET_FILE,
+    status,
+};
+
+pub(crate) const ADDRESSES_FILE: &str = "addresses.json";
+const ORCHESTRATOR_ENV_FILE: &str = "orchestrator/.env";
+
+/// Directory of the deployment where the bootstrapper writes its outputs, mounted on
+/// [`BOOTSTRAPPER_OUTPUT_MOUNT`]
+pub(crate) const BOOTSTRAPPER_OUTPUT_DIR: &str = "bootstrapper/output";
+pub(crate) const BOOTSTRAPPER_OUTPUT_MOUNT: &str = "/app/output";
+
+/// Variable of the compose env file read by the state override
+pub(crate) const CORE_CONTRACT_VARIABLE: &str = "CORE_CONTRACT_ADDRESS";
+const ORCHESTRATOR_CORE_CONTRACT_VARIABLE: &str = "MADARA_ORCHESTRATOR_L1_CORE_CONTRACT_ADDRESS";
+
+/// Keys of the core contract address in the output of the L1 setup, depending on the version
+/// of `madara-bootstrapper`
+const CORE_CONTRACT_KEYS: [&str; 2] = ["starknet_contract_address", "core_contract_address"];
+
+/// Contracts deployed by the bootstrapper, saved in `addresses.json` once its stages finish.
+///
+/// Fields are `None` when the bootstrapper didn't deploy the contract, e.g. the L2 contracts
+/// when `deploy_l2_contracts` is disabled.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DeploymentAddresses {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_contract: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_contract_implementation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l1_eth_bridge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_eth_bridge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l1_token_bridge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l2_token_bridge: Option<String>,
+    /// Parent fee token (ETH)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eth_fee_token: Option<String>,
+    /// Native fee token (STRK)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strk_fee_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oz_account_class_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argent_account_class_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub braavos_account_class_hash: Option<String>,
+}
+
+/// A contract listed by `addresses`
+#[derive(Debug, Serialize)]
+struct AddressEntry {
+    contract: &'static str,
+    address: String,
+}
+
+impl DeploymentAddresses {
+    /// Addresses saved in the deployment, `None` before the bootstrapper ran
+    pub fn load(deployment: &Deployment) -> anyhow::Result<Option<Self>> {
+        let path = deployment.path(ADDRESSES_FILE);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let addresses = serde_json::from_str(&fs::read_to_string(&path)?)
+            .with_context(|| format!("Invalid addresses file {}", path))?;
+        Ok(Some(addresses))
+    }
+
+    fn save(&self, deployment: &Deployment) -> anyhow::Result<()> {
+        fs::write(
+            deployment.path(ADDRESSES_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Read the outputs written by the bootstrapper stages. The fee tokens are predeployed by
+    /// Madara, so they're taken from the bootstrapper configuration when its output doesn't
+    /// list them.
+    fn collect(deployment: &Deployment) -> anyhow::Result<Self> {
+        let mut addresses = Self::default();
+        for stage in [Stage::BootstrapL1, Stage::BootstrapL2] {
+            if let Some(output) = read_output(deployment, stage)? {
+                addresses.merge(Self::from_stage_output(stage, &output)?);
+            }
+        }
+
+        let config_file = deployment.path(BOOTSTRAPPER_CONFIG_FILE);
+        if Path::new(&config_file).exists() {
+            let config = BootstrapperFileConfiguration::load(&config_file)?;
+            addresses
+                .eth_fee_token
+                .get_or_insert(config.fee_token_address);
+            addresses
+                .strk_fee_token
+                .get_or_insert(config.native_fee_token_address);
+        }
+
+        Ok(addresses)
+    }
+
+    /// Pick the fields of the output of a bootstrapper stage, the L1 setup must have deployed
+    /// the core contract the next stages are configured with
+    fn from_stage_output(stage: Stage, output: &Value) -> anyhow::Result<Self> {
+        let addresses = Self::from_output(output);
+        if stage == Stage::BootstrapL1 && addresses.core_contract.is_none() {
+            bail!(
+                "The bootstrapper output of the {} stage ({}) has no core contract address, expected a `{}` key",
+                stage,
+                output_file(stage).unwrap_or_default(),
+                CORE_CONTRACT_KEYS.join("` or `")
+            );
+        }
+        Ok(addresses)
+    }
+
+    /// Pick the known fields of an output of `madara-bootstrapper`, empty values are missing
+    fn from_output(output: &Value) -> Self {
+        let find = |paths: &[&str]| {
+            paths.iter().find_map(|path| {
+                path.split('.')
+                    .try_fold(output, |value, key| value.get(key))
+                    .and_then(Value::as_str)
+                    .filter(|address| !address.is_empty())
+                    .map(String::from)
+            })
+        };
+
+        Self {
+            core_contract: find(&CORE_CONTRACT_KEYS),
+            core_contract_implementation: find(&[
+                "starknet_contract_implementation_address",
+                "core_contract_implementation_address",
+            ]),
+            l1_eth_bridge: find(&["eth_bridge_setup_outputs.l1_bridge_address"]),
+            l2_eth_bridge: find(&["eth_bridge_setup_outputs.l2_eth_bridge_proxy_address"]),
+            l1_token_bridge: find(&["token_bridge_setup_outputs.l1_bridge_address"]),
+            l2_token_bridge: find(&["token_bridge_setup_outputs.l2_token_bridge"]),
+            eth_fee_token: find(&["eth_bridge_setup_outputs.l2_eth_proxy_address"]),
+            strk_fee_token: None,
+            udc: find(&["udc_setup_outputs.udc_address"]),
+            oz_account_class_hash: find(&[
+                "oz_account_class_hash",
+                "oz_account_cairo_1_class_hash",
+            ]),
+            argent_account_class_hash: find(&["argent_setup_outputs.argent_account_class_hash"]),
+            braavos_account_class_hash: find(&["braavos_setup_outputs.braavos_class_hash"]),
+        }
+    }
+
+    /// Keep the addresses already known, filling the missing ones from `other`
+    fn merge(&mut self, other: Self) {
+        let Self {
+            core_contract,
+            core_contract_implementation,
+            l1_eth_bridge,
+            l2_eth_bridge,
+            l1_token_bridge,
+            l2_token_bridge,
+            eth_fee_token,
+            strk_fee_token,
+            udc,
+            oz_account_class_hash,
+            argent_account_class_hash,
+            braavos_account_class_hash,
+        } = other;
+
+        self.core_contract = self.core_contract.take().or(core_contract);
+        self.core_contract_implementation = self
+            .core_contract_implementation
+            .take()
+            .or(core_contract_implementation);
+        self.l1_eth_bridge = self.l1_eth_bridge.take().or(l1_eth_bridge);
+        self.l2_eth_bridge = self.l2_eth_bridge.take().or(l2_eth_bridge);
+        self.l1_token_bridge = self.l1_token_bridge.take().or(l1_token_bridge);
+        self.l2_token_bridge = self.l2_token_bridge.take().or(l2_token_bridge);
+        self.eth_fee_token = self.eth_fee_token.take().or(eth_fee_token);
+        self.strk_fee_token = self.strk_fee_token.take().or(strk_fee_token);
+        self.udc = self.udc.take().or(udc);
+        self.oz_account_class_hash = self.oz_account_class_hash.take().or(oz_account_class_hash);
+        self.argent_account_class_hash = self
+            .argent_account_class_hash
+            .take()
+            .or(argent_account_class_hash);
+        self.braavos_account_class_hash = self
+            .braavos_account_class_hash
+            .take()
+            .or(braavos_account_class_hash);
+    }
+
+    fn entries(&self) -> Vec<AddressEntry> {
+        [
+            ("core_contract", &self.core_contract),
+            (
+                "core_contract_implementation",
+                &self.core_contract_implementation,
+            ),
+            ("l1_eth_bridge", &self.l1_eth_bridge),
+            ("l2_eth_bridge", &self.l2_eth_bridge),
+            ("l1_token_bridge", &self.l1_token_bridge),
+            ("l2_token_bridge", &self.l2_token_bridge),
+            ("eth_fee_token", &self.eth_fee_token),
+            ("strk_fee_token", &self.strk_fee_token),
+            ("udc", &self.udc),
+            ("oz_account_class_hash", &self.oz_account_class_hash),
+            ("argent_account_class_hash", &self.argent_account_class_hash),
+            (
+                "braavos_account_class_hash",
+                &self.braavos_account_class_hash,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(contract, address)| {
+            address
+                .clone()
+                .map(|address| AddressEntry { contract, address })
+        })
+        .collect()
+    }
+
+    /// Write the core contract address into the files rendered for the next stages: the
+    /// bootstrapper configuration of the L2 setup, the Madara preset, the state override and
+    /// the orchestrator env
+    pub fn apply(&self, deployment: &Deployment) -> anyhow::Result<()> {
+        let Some(core_contract) = &self.core_contract else {
+            return Ok(());
+        };
+
+        let config_file = deployment.path(BOOTSTRAPPER_CONFIG_FILE);
+        if Path::new(&config_file).exists() {
+            let mut config = BootstrapperFileConfiguration::load(&config_file)?;
+            config.core_contract_address = core_contract.clone();
+            if let Some(implementation) = &self.core_contract_implementation {
+                config.core_contract_implementation_address = implementation.clone();
+            }
+            config.save(&config_file)?;
+        }
+
+        let preset_file = deployment.path(APPCHAIN_PRESET_FILE);
+        if Path::new(&preset_file).exists() {
+            let mut preset = MadaraPresetConfiguration::load(&preset_file)?;
+            preset.eth_core_contract_address = core_contract.clone();
+            preset.save(&preset_file)?;
+        }
+
+        set_env_variable(
+            &deployment.env_file(),
+            CORE_CONTRACT_VARIABLE,
+            core_contract,
+        )?;
+        set_env_variable(
+            &deployment.path(ORCHESTRATOR_ENV_FILE),
+            ORCHESTRATOR_CORE_CONTRACT_VARIABLE,
+            core_contract,
+        )?;
+        Ok(())
+    }
+}
+
+/// Output file the bootstrapper writes in the given stage, relative to its output directory
+pub(crate) fn output_file(stage: Stage) -> Option<&'static str> {
+    match stage {
+        Stage::BootstrapL1 => Some("setup-l1.json"),
+        Stage::BootstrapL2 => Some("setup-l2.json"),
+        _ => None,
+    }
+}
+
+fn output_path(deployment: &Deployment, file: &str) -> String {
+    deployment.path(&format!("{}/{}", BOOTSTRAPPER_OUTPUT_DIR, file))
+}
+
+fn read_output(deployment: &Deployment, stage: Stage) -> anyhow::Result<Option<Value>> {
+    let Some(file) = output_file(stage) else {
+        return Ok(None);
+    };
+    let path = output_path(deployment, file);
+    if !Path::new(&path).exists() {
+        return Ok(None);
+    }
+    let output = serde_json::from_str(&fs::read_to_string(&path)?)
+        .with_context(|| format!("Invalid bootstrapper output {}", path))?;
+    Ok(Some(output))
+}
+
+/// Remove the output of a bootstrapper stage before it runs again, so addresses of a previous
+/// run aren't collected
+pub(crate) fn clear_output(deployment: &Deployment, stage: Stage) -> anyhow::Result<()> {
+    if let Some(file) = output_file(stage) {
+        let path = output_path(deployment, file);
+        if Path::new(&path).exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Collect the addresses once a bootstrapper stage finished, save them and update the files
+/// that depend on them
+pub(crate) fn update(deployment: &Deployment, stage: Stage) -> anyhow::Result<()> {
+    if output_file(stage).is_none() {
+        return Ok(());
+    }
+    let addresses = DeploymentAddresses::collect(deployment)?;
+    addresses.save(deployment)?;
+    addresses.apply(deployment)
+}
+
+/// Set a variable of an env file, replacing its current value
+fn set_env_variable(path: &str, key: &str, value: &str) -> anyhow::Result<()> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let prefix = format!("{}=", key);
+    let line = format!("{}{}", prefix, value);
+
+    let mut found = false;
+    let mut lines = content
+        .lines()
+        .map(|current| match current.starts_with(&prefix) {
+            true => {
+                found = true;
+                line.clone()
+            }
+            false => current.to_string(),
+        })
+        .collect::<Vec<_>>();
+    if !found {
+        lines.push(line);
+    }
+
+    fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+pub(crate) fn run(name: Option<String>) -> anyhow::Result<()> {
+    let deployment = Deployment::select(name)?;
+    let Some(addresses) = DeploymentAddresses::load(&deployment)? else {
+        bail!(
+            "No addresses recorded for `{}`, they're collected once the bootstrapper of an app-chain deployment finished",
+            deployment.name()
+        );
+    };
+
+    if output::is_json() {
+        return output::emit_table("addresses", addresses.entries());
+    }
+
+    let rows = addresses
+        .entries()
+        .into_iter()
+        .map(|entry| [entry.contract.to_string(), entry.address])
+        .collect::<Vec<_>>();
+    logger::note(
+        format!("Addresses of `{}`", deployment.name()),
+        status::align_columns(&["CONTRACT", "ADDRESS"], &rows).join("\n"),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_bootstrapper_outputs() {
+        let l1 = json!({
+            "starknet_contract_address": "0x9fe4",
+            "starknet_contract_implementation_address": "0xe7f1",
+        });
+        let l2 = json!({
+            "eth_bridge_setup_outputs": {
+                "l1_bridge_address": "0x8a79",
+                "l2_eth_proxy_address": "0x49d3",
+                "l2_eth_bridge_proxy_address": "0x1908",
+            },
+            "token_bridge_setup_outputs": { "l1_bridge_address": "0x0dcd" },
+            "braavos_setup_outputs": { "braavos_class_hash": "0x2c8c" },
+        });
+
+        let mut addresses = DeploymentAddresses::from_output(&l1);
+        addresses.merge(DeploymentAddresses::from_output(&l2));
+
+        assert_eq!(addresses.core_contract.as_deref(), Some("0x9fe4"));
+        assert_eq!(addresses.l1_eth_bridge.as_deref(), Some("0x8a79"));
+        assert_eq!(addresses.l1_token_bridge.as_deref(), Some("0x0dcd"));
+        assert_eq!(addresses.eth_fee_token.as_deref(), Some("0x49d3"));
+        assert_eq!(
+            addresses.braavos_account_class_hash.as_deref(),
+            Some("0x2c8c")
+        );
+        assert_eq!(addresses.udc, None);
+        assert_eq!(addresses.entries().len(), 7);
+    }
+
+    #[test]
+    fn test_missing_core_contract() {
+        let l1 = json!({ "starknet_contract_address": "" });
+        let e = DeploymentAddresses::from_stage_output(Stage::BootstrapL1, &l1).unwrap_err();
+        assert!(e.to_string().contains("`starknet_contract_address`"));
+
+        // The core contract comes from the L1 setup
+        let l2 = json!({ "udc_setup_outputs": { "udc_address": "0x41a7" } });
+        let addresses = DeploymentAddresses::from_stage_output(Stage::BootstrapL2, &l2).unwrap();
+        assert_eq!(addresses.udc.as_deref(), Some("0x41a7"));
+    }
+}
@@ -0,0 +1,542 @@
+use std::collections::BTreeMap;
+
+use madara_cli_config::compose::{
+    CommandLine, Compose, Dependency, DependencyCondition, Environment, Healthcheck, Service,
+};
+
+use madara_cli_types::stage::Stage;
+
+use crate::config::resources::ResourcesConfiguration;
+use crate::constants::{
+    DOCKERHUB_ORGANIZATION, REMOTE_BOOTSTRAPPER_IMAGE, REMOTE_HELPER_IMAGE, REMOTE_MADARA_IMAGE,
+    REMOTE_ORCHESTRATOR_IMAGE, REMOTE_PATHFINDER_IMAGE,
+};
+
+use super::addresses::{
+    self, BOOTSTRAPPER_OUTPUT_DIR, BOOTSTRAPPER_OUTPUT_MOUNT, CORE_CONTRACT_VARIABLE,
+};
+
+const FOUNDRY_IMAGE: &str = "ghcr.io/foundry-rs/foundry:v1.0.0";
+const MONGO_IMAGE: &str = "mongo";
+const LOCALSTACK_IMAGE: &str =
+    "localstack/localstack@sha256:763947722c6c8d33d5fbf7e8d52b4bddec5be35274a0998fdc6176d733375314";
+const MOCK_PROVER_IMAGE: &str = "gustavomoonsong/mock-prover:latest";
+// Address of the core contract deployed by the bootstrapper on a fresh Anvil, used until the
+// address it reported is written into the compose env file
+const DEFAULT_CORE_CONTRACT_ADDRESS: &str = "0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0";
+
+// Settings the app-chain compose file depends on
+pub(crate) struct AppChainCompose<'a> {
+    pub project_name: &'a str,
+    pub eth_priv_key: &'a str,
+    /// Location of `deps` seen from the deployment directory
+    pub deps_dir: &'a str,
+    pub ports: BTreeMap<&'static str, u16>,
+    pub resources: &'a ResourcesConfiguration,
+    /// Use the images built locally instead of the published ones
+    pub local_images: bool,
+    pub dummy_prover: bool,
+    pub deploy_l2_contracts: bool,
+}
+
+impl AppChainCompose<'_> {
+    /// Build the compose document with every service of the app-chain
+    pub fn build(&self) -> Compose {
+        let mut services = BTreeMap::from([
+            ("anvil", self.anvil()),
+            ("mock_verifier_contract", self.mock_verifier_contract()),
+            ("bootstrapper_l1", self.bootstrapper_l1()),
+            ("madara", self.madara()),
+            ("bootstrapper_l2", self.bootstrapper_l2()),
+            ("override_state", self.override_state()),
+            ("pathfinder", self.pathfinder()),
+            ("mongodb", self.mongodb()),
+            ("init_mongo", self.init_mongo()),
+            ("localstack", self.localstack()),
+            ("orchestrator", self.orchestrator()),
+        ]);
+        if self.dummy_prover {
+            services.insert("prover", self.prover());
+        }
+
+        Compose {
+            name: Some(self.project_name.to_string()),
+            services: services
+                .into_iter()
+                .map(|(name, service)| (name.to_string(), service))
+                .collect(),
+            secrets: BTreeMap::new(),
+        }
+    }
+
+    fn anvil(&self) -> Service {
+        Service {
+            image: FOUNDRY_IMAGE.to_string(),
+            container_name: self.container_name("anvil"),
+            cpus: self.cpus("anvil"),
+            mem_limit: self.mem_limit("anvil"),
+            ports: Some(vec![self.port("anvil_rpc", 8545)]),
+            volumes: Some(vec!["${ANVIL_DATA_DIR}:/tmp/anvil".to_string()]),
+            entrypoint: Some(list([
+                "anvil",
+                "--host",
+                "0.0.0.0",
+                "--block-time",
+                "1",
+                "--code-size-limit",
+                "300000",
+                "--dump-state",
+                "/tmp/anvil/state.json",
+            ])),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn mock_verifier_contract(&self) -> Service {
+        Service {
+            image: FOUNDRY_IMAGE.to_string(),
+            container_name: self.container_name("mock_verifier"),
+            working_dir: Some("/tmp".to_string()),
+            entrypoint: Some(CommandLine::String("/bin/bash".to_string())),
+            volumes: Some(vec![
+                self.helper_volume(
+                    "mock_verifier_contract.sh",
+                    "/tmp/mock_verifier_contract.sh",
+                ),
+                self.helper_volume("MockGPSVerifier.sol", "/tmp/MockGPSVerifier.sol"),
+            ]),
+            depends_on: depends_on([("anvil", DependencyCondition::ServiceStarted)]),
+            command: Some(bash(&format!(
+                "sleep 5 && ./mock_verifier_contract.sh {}",
+                self.eth_priv_key
+            ))),
+            ..Default::default()
+        }
+    }
+
+    fn bootstrapper_l1(&self) -> Service {
+        Service {
+            image: self.image("bootstrapper", REMOTE_BOOTSTRAPPER_IMAGE),
+            container_name: self.container_name("bootstrapper_l1"),
+            depends_on: depends_on([(
+                "mock_verifier_contract",
+                DependencyCondition::ServiceCompletedSuccessfully,
+            )]),
+            volumes: Some(bootstrapper_volumes()),
+            command: Some(bash(&format!(
+                "sleep 5 && ./madara-bootstrapper --mode setup-l1 --config src/configs/devnet.json {}",
+                bootstrapper_output(Stage::BootstrapL1)
+            ))),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn madara(&self) -> Service {
+        Service {
+            image: self.image("madara", REMOTE_MADARA_IMAGE),
+            container_name: self.container_name("madara"),
+            cpus: self.cpus("madara"),
+            mem_limit: self.mem_limit("madara"),
+            ports: Some(vec![
+                self.port("madara_rpc", 9945),
+                self.port("madara_gateway", 8080),
+            ]),
+            depends_on: depends_on([(
+                "bootstrapper_l1",
+                DependencyCondition::ServiceCompletedSuccessfully,
+            )]),
+            volumes: Some(vec![
+                "${MADARA_DATA_DIR}:/usr/share/madara/data".to_string(),
+                "./madara/madara-runner.sh:/usr/local/bin/runner.sh:ro".to_string(),
+                "./madara/configs/presets:/usr/local/bin/configs/presets".to_string(),
+            ]),
+            entrypoint: Some(list(["/usr/local/bin/runner.sh"])),
+            restart: Some("unless-stopped".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn bootstrapper_l2(&self) -> Service {
+        let command = match self.deploy_l2_contracts {
+            true => format!(
+                "sleep 5 && ./madara-bootstrapper --mode setup-l2 --config src/configs/devnet.json {}",
+                bootstrapper_output(Stage::BootstrapL2)
+            ),
+            false => "sleep 5 && echo 'Bootstrapper L2 contracts are disabled'".to_string(),
+        };
+
+        Service {
+            image: self.image("bootstrapper", REMOTE_BOOTSTRAPPER_IMAGE),
+            container_name: self.container_name("bootstrapper_l2"),
+            depends_on: depends_on([("madara", DependencyCondition::ServiceStarted)]),
+            volumes: Some(bootstrapper_volumes()),
+            command: Some(bash(&command)),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn override_state(&self) -> Service {
+        Service {
+            image: self.image("helper", REMOTE_HELPER_IMAGE),
+            container_name: self.container_name("override_state"),
+            working_dir: Some("/app".to_string()),
+            entrypoint: Some(CommandLine::String("/bin/bash".to_string())),
+            depends_on: depends_on([(
+                "bootstrapper_l2",
+                DependencyCondition::ServiceCompletedSuccessfully,
+            )]),
+            volumes: Some(vec![
+                self.helper_volume("override_state.js", "/app/override_state.js")
+            ]),
+            environment: Some(Environment::Map(BTreeMap::from([
+                (
+                    "MADARA_ORCHESTRATOR_ETHEREUM_PRIVATE_KEY".to_string(),
+                    self.eth_priv_key.to_string(),
+                ),
+                (
+                    CORE_CONTRACT_VARIABLE.to_string(),
+                    format!(
+                        "${{{}:-{}}}",
+                        CORE_CONTRACT_VARIABLE, DEFAULT_CORE_CONTRACT_ADDRESS
+                    ),
+                ),
+            ]))),
+            command: Some(bash("node override_state.js")),
+            ..Default::default()
+        }
+    }
+
+    fn pathfinder(&self) -> Service {
+        Service {
+            image: self.image("pathfinder", REMOTE_PATHFINDER_IMAGE),
+            container_name: self.container_name("pathfinder"),
+            cpus: self.cpus("pathfinder"),
+            mem_limit: self.mem_limit("pathfinder"),
+            ports: Some(vec![self.port("pathfinder_rpc", 9545)]),
+            depends_on: depends_on([("madara", DependencyCondition::ServiceStarted)]),
+            volumes: Some(vec![
+                "${PATHFINDER_DATA_DIR}:/usr/share/pathfinder/data".to_string(),
+                "./pathfinder/pathfinder-runner.sh:/usr/local/bin/runner.sh:ro".to_string(),
+            ]),
+            entrypoint: Some(list(["/usr/local/bin/runner.sh"])),
+            restart: Some("unless-stopped".to_string()),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn mongodb(&self) -> Service {
+        Service {
+            image: MONGO_IMAGE.to_string(),
+            container_name: self.container_name("mongo"),
+            ports: Some(vec![self.port("mongodb", 27017)]),
+            restart: Some("unless-stopped".to_string()),
+            depends_on: depends_on([("madara", DependencyCondition::ServiceStarted)]),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn init_mongo(&self) -> Service {
+        Service {
+            image: MONGO_IMAGE.to_string(),
+            container_name: self.container_name("init_mongo"),
+            depends_on: depends_on([(
+                "bootstrapper_l2",
+                DependencyCondition::ServiceCompletedSuccessfully,
+            )]),
+            entrypoint: Some(list([
+                "bash",
+                "-c",
+                "mongosh --host mongodb --eval 'load(\"/init.js\")'",
+            ])),
+            volumes: Some(vec![self.helper_volume("init_mongo.js", "/init.js")]),
+            ..Default::default()
+        }
+    }
+
+    fn localstack(&self) -> Service {
+        Service {
+            image: LOCALSTACK_IMAGE.to_string(),
+            container_name: self.container_name("localstack"),
+            ports: Some(vec![self.port("localstack", 4566)]),
+            restart: Some("unless-stopped".to_string()),
+            depends_on: depends_on([("madara", DependencyCondition::ServiceStarted)]),
+            healthcheck: Some(Healthcheck {
+                test: [
+                    "CMD",
+                    "curl",
+                    "-f",
+                    "http://localhost:4566/_localstack/health",
+                ]
+                .map(String::from)
+                .to_vec(),
+                interval: Some("30s".to_string()),
+                timeout: Some("10s".to_string()),
+                retries: Some(5),
+                start_period: None,
+            }),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn prover(&self) -> Service {
+        Service {
+            image: MOCK_PROVER_IMAGE.to_string(),
+            container_name: self.container_name("prover"),
+            ports: Some(vec![self.port("prover", 6000)]),
+            depends_on: depends_on([("madara", DependencyCondition::ServiceStarted)]),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn orchestrator(&self) -> Service {
+        Service {
+            image: self.image("orchestrator", REMOTE_ORCHESTRATOR_IMAGE),
+            container_name: self.container_name("orchestrator"),
+            cpus: self.cpus("orchestrator"),
+            mem_limit: self.mem_limit("orchestrator"),
+            volumes: Some(vec![
+                "./orchestrator/.env:/usr/local/bin/.env_tmp".to_string(),
+                "./orchestrator/run_orchestrator.sh:/usr/local/bin/run_orchestrator.sh".to_string(),
+            ]),
+            entrypoint: Some(list(["/usr/local/bin/run_orchestrator.sh"])),
+            depends_on: depends_on([
+                (
+                    "bootstrapper_l2",
+                    DependencyCondition::ServiceCompletedSuccessfully,
+                ),
+                ("localstack", DependencyCondition::ServiceHealthy),
+            ]),
+            attach: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn cpus(&self, service: &str) -> Option<String> {
+        Some(self.resources.limits(service).cpus.to_string())
+    }
+
+    fn mem_limit(&self, service: &str) -> Option<String> {
+        Some(self.resources.limits(service).memory.to_string())
+    }
+
+    fn container_name(&self, container: &str) -> Option<String> {
+        Some(format!("{}-{}", self.project_name, container))
+    }
+
+    fn helper_volume(&self, file: &str, target: &str) -> String {
+        format!("{}/helper/{}:{}", self.deps_dir, file, target)
+    }
+
+    fn image(&self, name: &str, remote_version: &str) -> String {
+        match self.local_images {
+            true => format!("{}:latest", name),
+            false => format!("{}{}:{}", DOCKERHUB_ORGANIZATION, name, remote_version),
+        }
+    }
+
+    // Ports missing from the plan are published on the container port
+    fn port(&self, key: &str, container_port: u16) -> String {
+        let host_port = self.ports.get(key).copied().unwrap_or(container_port);
+        format!("{}:{}", host_port, container_port)
+    }
+}
+
+fn depends_on<const N: usize>(
+    dependencies: [(&str, DependencyCondition); N],
+) -> Option<BTreeMap<String, Dependency>> {
+    let dependencies = dependencies
+        .into_iter()
+        .map(|(service, condition)| (service.to_string(), Dependency { condition }))
+        .collect();
+    Some(dependencies)
+}
+
+fn list<const N: usize>(args: [&str; N]) -> CommandLine {
+    CommandLine::List(args.map(String::from).to_vec())
+}
+
+// Arguments of the `/bin/bash` entrypoints
+fn bash(script: &str) -> CommandLine {
+    list(["-c", script])
+}
+
+fn bootstrapper_volumes() -> Vec<String> {
+    vec![
+        "./bootstrapper/devnet.json:/app/src/configs/devnet.json".to_string(),
+        format!(
+            "./{}:{}",
+            BOOTSTRAPPER_OUTPUT_DIR, BOOTSTRAPPER_OUTPUT_MOUNT
+        ),
+    ]
+}
+
+// Arguments writing the output of a bootstrapper stage, read back into `addresses.json`
+fn bootstrapper_output(stage: Stage) -> String {
+    let file = addresses::output_file(stage).unwrap_or_default();
+    format!("--output-file {}/{}", BOOTSTRAPPER_OUTPUT_MOUNT, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use madara_cli_types::stage::Stage;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::commands::{deployment::deps_dir_from, pipeline};
+
+    fn app_chain_settings(resources: &ResourcesConfiguration) -> AppChainCompose<'_> {
+        AppChainCompose {
+            project_name: "appchain",
+            eth_priv_key: "0xabc",
+            deps_dir: "../../deps",
+            ports: BTreeMap::from([("madara_rpc", 19945)]),
+            resources,
+            local_images: false,
+            dummy_prover: true,
+            deploy_l2_contracts: true,
+        }
+    }
+
+    fn app_chain(dummy_prover: bool, deploy_l2_contracts: bool) -> Compose {
+        AppChainCompose {
+            dummy_prover,
+            deploy_l2_contracts,
+            ..app_chain_settings(&ResourcesConfiguration::default())
+        }
+        .build()
+    }
+
+    fn command(service: &Service) -> Vec<String> {
+        match &service.command {
+            Some(CommandLine::List(args)) => args.clone(),
+            other => panic!("Unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_services_are_toggled_by_config() {
+        let compose = app_chain(false, false);
+        assert!(!compose.services.contains_key("prover"));
+        assert!(command(&compose.services["bootstrapper_l2"])[1].contains("disabled"));
+
+        let compose = app_chain(true, true);
+        assert_eq!(
+            compose.services["prover"].ports,
+            Some(vec!["6000:6000".to_string()])
+        );
+        assert!(command(&compose.services["bootstrapper_l2"])[1].contains("--mode setup-l2"));
+    }
+
+    #[test]
+    fn test_every_service_belongs_to_a_stage() {
+        let compose = app_chain(true, true);
+        let staged = Stage::iter()
+            .flat_map(pipeline::stage_services)
+            .map(|(service, _)| *service)
+            .collect::<Vec<_>>();
+
+        for service in compose.services.keys() {
+            let count = staged.iter().filter(|s| *s == service).count();
+            assert_eq!(count, 1, "`{}` must be started by one stage", service);
+        }
+    }
+
+    #[test]
+    fn test_services_wait_for_their_dependencies() {
+        let compose = app_chain(true, true);
+
+        // Every dependency must be a service of the document
+        for service in compose.services.values() {
+            for dependency in service.depends_on.iter().flat_map(BTreeMap::keys) {
+                assert!(compose.services.contains_key(dependency), "{}", dependency);
+            }
+        }
+        let orchestrator = compose.services["orchestrator"]
+            .depends_on
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            orchestrator["localstack"].condition,
+            DependencyCondition::ServiceHealthy
+        );
+        assert_eq!(
+            orchestrator["bootstrapper_l2"].condition,
+            DependencyCondition::ServiceCompletedSuccessfully
+        );
+    }
+
+    #[test]
+    fn test_serialized_document() {
+        let compose = app_chain(false, true);
+        let yaml = serde_yaml::to_string(&compose).unwrap();
+        let parsed: Compose = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(parsed.name.as_deref(), Some("appchain"));
+        assert_eq!(parsed.services.len(), compose.services.len());
+        let madara = &parsed.services["madara"];
+        assert_eq!(madara.container_name.as_deref(), Some("appchain-madara"));
+        assert_eq!(
+            madara.ports,
+            Some(vec!["19945:9945".to_string(), "8080:8080".to_string()])
+        );
+        assert_eq!(
+            madara.image,
+            format!("{}madara:{}", DOCKERHUB_ORGANIZATION, REMOTE_MADARA_IMAGE)
+        );
+        assert_eq!(
+            command(&parsed.services["mock_verifier_contract"]),
+            ["-c", "sleep 5 && ./mock_verifier_contract.sh 0xabc"]
+        );
+        assert_eq!(parsed.services["anvil"].attach, Some(false));
+        assert_eq!(madara.cpus.as_deref(), Some("4"));
+        assert_eq!(madara.mem_limit.as_deref(), Some("16gb"));
+        assert!(!yaml.contains("{{"));
+    }
+
+    #[test]
+    fn test_mounts_outside_the_workspace() {
+        // Rendered with `--dry-run` into a directory outside the workspace
+        let workspace = fs::canonicalize("../..").unwrap();
+        let output_dir = std::env::temp_dir().join(format!("madara-cli-{}", std::process::id()));
+        let deployment_dir = output_dir.join("app-chain");
+        fs::create_dir_all(&deployment_dir).unwrap();
+        let deps_dir = deps_dir_from(&workspace, &deployment_dir);
+
+        let compose_file = deployment_dir.join("compose.yaml");
+        AppChainCompose {
+            deps_dir: &deps_dir.to_string_lossy(),
+            ..app_chain_settings(&ResourcesConfiguration::default())
+        }
+        .build()
+        .save(&compose_file.to_string_lossy())
+        .unwrap();
+        let compose = Compose::load(&compose_file.to_string_lossy()).unwrap();
+        fs::remove_dir_all(&output_dir).unwrap();
+
+        let volumes = compose
+            .services
+            .values()
+            .flat_map(|service| service.volumes.iter().flatten());
+        for volume in volumes {
+            let source = volume.split(':').next().unwrap();
+            // Data directories come from the env file, `./` files are rendered with the compose
+            if source.starts_with("${") || source.starts_with("./") {
+                continue;
+            }
+            assert!(
+                deployment_dir.join(source).exists(),
+                "{} doesn't exist",
+                source
+            );
+        }
+    }
+}
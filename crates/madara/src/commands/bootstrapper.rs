@@ -1,14 +1,19 @@
-use madara_cli_common::docker;
+use madara_cli_common::runtime::runtime;
 use xshell::Shell;
 
-use crate::config::{bootstrapper::BootstrapperConfiguration, global_config::Config, EthWallet};
+use crate::config::{
+    bootstrapper::BootstrapperFileConfiguration, global_config::Config, EthWallet,
+};
+
+use super::deployment::Deployment;
 
 const BOOTSTRAPPER_REPO_PATH: &str = "deps/bootstrapper";
 const BOOTSTRAPPER_DOCKER_IMAGE: &str = "bootstrapper";
-const BOOTSTRAPPER_CONFIG_FILE: &str = "deps/bootstrapper/devnet.json";
+const BOOTSTRAPPER_CONFIG_TEMPLATE: &str = "deps/bootstrapper/devnet.json";
+pub(crate) const BOOTSTRAPPER_CONFIG_FILE: &str = "bootstrapper/devnet.json";
 
 pub fn build_image(shell: &Shell) -> anyhow::Result<()> {
-    docker::build_image(
+    runtime().build_image(
         shell,
         BOOTSTRAPPER_REPO_PATH.to_string(),
         BOOTSTRAPPER_DOCKER_IMAGE.to_string(),
@@ -17,30 +22,45 @@ pub fn build_image(shell: &Shell) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn process_params(global_config: &Config) -> anyhow::Result<()> {
+pub(crate) fn process_params(
+    global_config: &Config,
+    deployment: &Deployment,
+) -> anyhow::Result<()> {
     let global_config = global_config.clone();
 
-    let mut config = BootstrapperConfiguration::load(BOOTSTRAPPER_CONFIG_FILE);
+    let mut config = BootstrapperFileConfiguration::load(BOOTSTRAPPER_CONFIG_TEMPLATE)?;
 
     let eth_wallet = EthWallet::new(
         global_config.eth_wallet.eth_priv_key,
         global_config.eth_wallet.l1_multisig_address,
     );
 
-    assert_eq!(
-        eth_wallet.l1_deployer_address.to_lowercase(),
-        global_config.eth_wallet.l1_deployer_address.to_lowercase(),
-        "L1 deployer address is not derived from provided private key"
-    );
-
+    // Values shared with the L1, wallet and Madara sections
     config.eth_rpc = global_config.l1_config.eth_rpc;
+    config.eth_chain_id = global_config.l1_config.eth_chain_id;
+    config.verifier_address = global_config.l1_config.verifier_address;
     config.eth_priv_key = eth_wallet.eth_priv_key;
     config.l1_deployer_address = eth_wallet.l1_deployer_address;
     config.l1_multisig_address = eth_wallet.l1_multisig_address;
     config.operator_address = eth_wallet.l1_operator_address;
     config.app_chain_id = global_config.madara.app_chain_id;
+    config.fee_token_address = global_config.madara.parent_fee_token_address;
+    config.native_fee_token_address = global_config.madara.native_fee_token_address;
+
+    // Values of the `[bootstrapper]` section
+    let bootstrapper = global_config.bootstrapper;
+    config.rollup_seq_url = bootstrapper.rollup_seq_url;
+    config.rollup_declare_v0_seq_url = bootstrapper.rollup_declare_v0_seq_url;
+    config.rollup_priv_key = bootstrapper.rollup_priv_key;
+    config.l1_wait_time = bootstrapper.l1_wait_time.to_string();
+    config.sn_os_program_hash = bootstrapper.sn_os_program_hash;
+    config.config_hash_version = bootstrapper.config_hash_version;
+    config.core_contract_mode = bootstrapper.core_contract_mode;
+    config.cross_chain_wait_time = bootstrapper.cross_chain_wait_time;
+    config.l2_multisig_address = bootstrapper.l2_multisig_address;
+    config.dev = bootstrapper.dev;
 
-    config.save(BOOTSTRAPPER_CONFIG_FILE);
+    config.save(&deployment.path(BOOTSTRAPPER_CONFIG_FILE))?;
 
     Ok(())
 }
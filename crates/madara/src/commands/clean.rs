@@ -0,0 +1,85 @@
+use anyhow::bail;
+use madara_cli_common::{config::global_config, logger};
+use xshell::Shell;
+
+use super::deployment::Deployment;
+
+/// Remove the files rendered into the deployment directories, keeping the data of the
+/// services. Deployments with containers are left alone, their compose file is needed to
+/// stop them.
+pub(crate) fn run(name: Option<String>, shell: &Shell) -> anyhow::Result<()> {
+    let deployments = match &name {
+        Some(name) => vec![Deployment::load(name)?],
+        None => Deployment::all()?,
+    };
+    if deployments.is_empty() {
+        logger::outro("No deployment found, nothing to clean");
+        return Ok(());
+    }
+
+    let dry_run = global_config().is_dry_run();
+    let mut cleaned = 0;
+    for deployment in deployments {
+        // Without its containers, there's no telling whether the compose file is still needed
+        let containers = match deployment.containers(shell) {
+            Ok(containers) => containers,
+            Err(e) if name.is_none() => {
+                logger::warn(format!(
+                    "Skipping deployment `{}`, failed to list its containers: {:#}",
+                    deployment.name(),
+                    e
+                ));
+                continue;
+            }
+            Err(e) => {
+                return Err(e.context(format!(
+                    "Failed to list the containers of deployment `{}`",
+                    deployment.name()
+                )))
+            }
+        };
+        if !containers.is_empty() {
+            let message = format!(
+                "Deployment `{}` has containers, stop it first with `stop {}`",
+                deployment.name(),
+                deployment.name()
+            );
+            match name {
+                Some(_) => bail!(message),
+                None => {
+                    logger::warn(message);
+                    continue;
+                }
+            }
+        }
+
+        let files = deployment.rendered_files()?;
+        if dry_run {
+            for file in &files {
+                logger::step(format!(
+                    "Dry run, skipping the removal of: {}",
+                    file.display()
+                ));
+            }
+            continue;
+        }
+
+        deployment.remove_rendered_files()?;
+        logger::info(format!(
+            "Removed {} rendered file(s) from {}",
+            files.len(),
+            deployment.dir()
+        ));
+        cleaned += 1;
+    }
+
+    if dry_run {
+        logger::outro("Dry run of `clean` done, nothing was removed");
+    } else {
+        logger::outro(format!(
+            "Cleaned {} deployment(s), `create` renders their files again from the templates",
+            cleaned
+        ));
+    }
+    Ok(())
+}
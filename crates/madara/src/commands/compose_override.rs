@@ -0,0 +1,204 @@
+use std::fs;
+
+use anyhow::anyhow;
+use madara_cli_common::logger;
+use madara_cli_config::compose::Compose;
+use serde_yaml::{Mapping, Value};
+
+use crate::config::compose::ComposeConfiguration;
+
+use super::deployment::Deployment;
+
+/// Merge the `[compose.overrides]` section, then every override file, into the compose file
+/// rendered for the deployment
+pub(crate) fn apply(
+    deployment: &Deployment,
+    config: &ComposeConfiguration,
+    files: &[String],
+) -> anyhow::Result<()> {
+    if config.overrides.is_empty() && files.is_empty() {
+        return Ok(());
+    }
+
+    let compose_file = deployment.compose_file();
+    let mut compose: Value = serde_yaml::from_str(&fs::read_to_string(&compose_file)?)?;
+
+    if !config.overrides.is_empty() {
+        let services = serde_yaml::to_value(&config.overrides)?;
+        let overlay = Mapping::from_iter([(Value::from("services"), services)]);
+        merge(&mut compose, Value::Mapping(overlay));
+    }
+    for file in files {
+        let content = fs::read_to_string(file)
+            .map_err(|e| anyhow!("Failed to read the compose override {}: {}", file, e))?;
+        let overlay = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse the compose override {}: {}", file, e))?;
+        merge(&mut compose, overlay);
+    }
+
+    // Catch overrides that break the document, e.g. a service without an image
+    serde_yaml::from_value::<Compose>(compose.clone()).map_err(|e| {
+        anyhow!(
+            "The compose overrides produce an invalid compose file: {}",
+            e
+        )
+    })?;
+    fs::write(&compose_file, serde_yaml::to_string(&compose)?)?;
+
+    logger::info(format!(
+        "Merged {} compose override(s) into {}",
+        files.len() + usize::from(!config.overrides.is_empty()),
+        compose_file
+    ));
+    Ok(())
+}
+
+/// Merge `overlay` into `base`: mappings are merged key by key, lists are appended without
+/// duplicates and any other value is replaced. The entries of `environment`, `ports` and
+/// `volumes` lists replace the ones they override, see [`entry_key`].
+fn merge(base: &mut Value, overlay: Value) {
+    merge_field(base, overlay, None)
+}
+
+fn merge_field(base: &mut Value, overlay: Value, field: Option<&str>) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_field(existing, value, key.as_str()),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(overlay)) => {
+            for value in overlay {
+                let key = field.and_then(|field| entry_key(field, &value));
+                let overridden = key.and_then(|key| {
+                    base.iter().position(|entry| {
+                        field.and_then(|field| entry_key(field, entry)) == Some(key)
+                    })
+                });
+                match overridden {
+                    Some(index) => base[index] = value,
+                    None if !base.contains(&value) => base.push(value),
+                    None => {}
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// What an entry of a service's `field` list sets, two entries setting the same thing
+/// override each other: the variable of `KEY=VALUE` environment entries, the container
+/// port of `ports` entries and the container path of `volumes` entries
+fn entry_key<'a>(field: &str, entry: &'a Value) -> Option<&'a str> {
+    let entry = entry.as_str()?;
+    match field {
+        "environment" => entry.split('=').next(),
+        "ports" => entry.rsplit(':').next(),
+        "volumes" => entry.split(':').nth(1).or(Some(entry)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_services() {
+        let mut base: Value = serde_yaml::from_str(
+            r#"
+services:
+  madara:
+    image: madara:latest
+    mem_limit: 16gb
+    ports:
+      - "9944:9944"
+    environment:
+      - RPC_API_KEY_FILE=/run/secrets/rpc_api_key
+      - RUST_LOG=info
+    volumes:
+      - ./data:/var/lib/madara
+      - ./secrets:/run/secrets:ro
+"#,
+        )
+        .unwrap();
+        let overlay = serde_yaml::from_str(
+            r#"
+services:
+  madara:
+    image: madara:dev
+    ports:
+      - "127.0.0.1:9955:9944"
+      - "9945:9945"
+    environment:
+      - RUST_LOG=debug
+      - RPC_API_KEY_FILE=/run/secrets/rpc_api_key
+      - EXTRA=1
+    volumes:
+      - /mnt/madara:/var/lib/madara
+  grafana:
+    image: grafana/grafana
+networks:
+  monitoring: {}
+"#,
+        )
+        .unwrap();
+
+        merge(&mut base, overlay);
+        let compose: Compose = serde_yaml::from_value(base.clone()).unwrap();
+
+        let madara = &compose.services["madara"];
+        assert_eq!(madara.image, "madara:dev");
+        assert_eq!(madara.mem_limit.as_deref(), Some("16gb"));
+        assert_eq!(
+            madara.ports,
+            Some(vec![
+                "127.0.0.1:9955:9944".to_string(),
+                "9945:9945".to_string()
+            ])
+        );
+        let list = |field: &str| {
+            base["services"]["madara"][field]
+                .as_sequence()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.as_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            list("environment"),
+            vec![
+                "RPC_API_KEY_FILE=/run/secrets/rpc_api_key",
+                "RUST_LOG=debug",
+                "EXTRA=1"
+            ]
+        );
+        assert_eq!(
+            list("volumes"),
+            vec!["/mnt/madara:/var/lib/madara", "./secrets:/run/secrets:ro"]
+        );
+        assert!(compose.services.contains_key("grafana"));
+        assert!(base["networks"]["monitoring"].is_mapping());
+    }
+
+    #[test]
+    fn test_config_overrides_are_services() {
+        let config: ComposeConfiguration = toml::from_str(
+            r#"
+[overrides.madara]
+mem_limit = "8gb"
+environment = ["RUST_LOG=debug"]
+"#,
+        )
+        .unwrap();
+        let overrides = serde_yaml::to_value(&config.overrides).unwrap();
+
+        assert_eq!(overrides["madara"]["mem_limit"], "8gb");
+        assert_eq!(overrides["madara"]["environment"][0], "RUST_LOG=debug");
+    }
+}
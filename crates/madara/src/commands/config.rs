@@ -0,0 +1,137 @@
+use std::{fmt::Write, fs};
+
+use anyhow::{bail, Context};
+use figment::{
+    providers::{Format, Toml},
+    Figment, Provider,
+};
+use madara_cli_common::{
+    config::global_config,
+    logger,
+    output::{self, Event},
+};
+
+use crate::config::{
+    global_config::{Config, ConfigSection},
+    io,
+    overrides::{env_var, ENV_SOURCE, SET_FLAG_SOURCE},
+    LOCAL_CONFIG_NAME, LOCAL_CONFIG_TEMPLATE,
+};
+
+/// Print the configuration file, or with `resolved` the configuration merged from every
+/// source with the origin of each value
+pub(crate) fn show(resolved: bool) -> anyhow::Result<()> {
+    let global = global_config();
+    let config_file = global.config_file.as_deref();
+
+    if !resolved {
+        match config_file {
+            Some(file_path) => {
+                io::ensure_exists(file_path)?;
+                print_document(&fs::read_to_string(file_path)?);
+            }
+            None => print_document(LOCAL_CONFIG_TEMPLATE),
+        }
+        return Ok(());
+    }
+
+    let figment = Config::figment(config_file, &global.overrides)?;
+    let config: Config = io::extract(&figment, config_file.unwrap_or(LOCAL_CONFIG_NAME))?;
+    print_document(&render_resolved(&config, &figment)?);
+    Ok(())
+}
+
+fn print_document(content: &str) {
+    if output::is_json() {
+        output::emit(Event::Document {
+            name: "config".to_string(),
+            content: content.to_string(),
+        });
+    } else {
+        print!("{}", content);
+    }
+}
+
+/// Check a configuration file, merged with the other configuration sources, and report
+/// every problem found
+pub(crate) fn validate(config_file: Option<String>) -> anyhow::Result<()> {
+    let global = global_config();
+    let config_file = config_file.or_else(|| global.config_file.clone());
+    let origin = config_file
+        .as_deref()
+        .unwrap_or(LOCAL_CONFIG_NAME)
+        .to_string();
+
+    logger::intro(format!("Validating {}", origin));
+    let config = Config::resolve(config_file.as_deref(), &global.overrides)?;
+
+    let problems = config.problems();
+    if problems.is_empty() {
+        logger::outro("Configuration is valid");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        logger::error(problem);
+    }
+    bail!("Found {} problem(s) in {}", problems.len(), origin);
+}
+
+/// Prompt again for a single section of an existing configuration file and save it in
+/// place, keeping the comments of the file
+pub(crate) fn edit(section: ConfigSection, config_file: Option<String>) -> anyhow::Result<()> {
+    let global = global_config();
+    let config_file = config_file
+        .or_else(|| global.config_file.clone())
+        .context("No configuration file to edit, pass it as argument or with --config-file")?;
+
+    logger::intro(format!("Editing the {} of {}", section, config_file));
+    let mut config = Config::load(&config_file)?;
+    section.init(&mut config, global.default)?;
+    config.save_in_place(&config_file)?;
+
+    logger::outro(format!("Configuration saved to {}", config_file));
+    Ok(())
+}
+
+fn render_resolved(config: &Config, figment: &Figment) -> anyhow::Result<String> {
+    let toml::Value::Table(sections) = toml::Value::try_from(config)? else {
+        bail!("Configuration is not a TOML table");
+    };
+
+    let mut output = String::new();
+    for (section, values) in sections {
+        writeln!(output, "[{}]", section)?;
+        if let toml::Value::Table(values) = values {
+            for (key, value) in values {
+                let origin = value_origin(figment, &format!("{}.{}", section, key));
+                writeln!(output, "{} = {}  # {}", key, value, origin)?;
+            }
+        }
+        writeln!(output)?;
+    }
+
+    Ok(output)
+}
+
+/// Describe the source that provided the resolved value of a dotted key
+fn value_origin(figment: &Figment, key: &str) -> String {
+    let Some(metadata) = figment.find_metadata(key) else {
+        return "default".to_string();
+    };
+
+    if let Some(file_path) = metadata.source.as_ref().and_then(|s| s.file_path()) {
+        return file_path.display().to_string();
+    }
+    // `local.toml` is the only configuration embedded as a string
+    if metadata.name == Toml::string("").metadata().name {
+        return LOCAL_CONFIG_NAME.to_string();
+    }
+    if metadata.name == ENV_SOURCE {
+        return env_var(key);
+    }
+    if metadata.name == SET_FLAG_SOURCE {
+        return SET_FLAG_SOURCE.to_string();
+    }
+    "default".to_string()
+}
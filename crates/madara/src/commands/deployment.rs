@@ -0,0 +1,462 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use madara_cli_common::{
+    config::global_config,
+    docker::ComposeContainer,
+    output::{self, Event},
+    runtime::runtime,
+    PromptSelect,
+};
+use madara_cli_types::madara::MadaraMode;
+use serde::{Deserialize, Serialize};
+use xshell::Shell;
+
+use crate::constants::{DEPLOYMENTS_DIR, DEPS_REPO_PATH};
+
+use super::{
+    addresses::{ADDRESSES_FILE, BOOTSTRAPPER_OUTPUT_DIR},
+    pipeline::STATE_FILE,
+};
+
+// Variables written by `create` into the compose env files that point to bind-mounted data
+const DATA_DIR_VARIABLES: [&str; 3] = ["MADARA_DATA_DIR", "ANVIL_DATA_DIR", "PATHFINDER_DATA_DIR"];
+
+const DEPLOYMENT_FILE: &str = "deployment.toml";
+const COMPOSE_FILE: &str = "compose.yaml";
+const COMPOSE_ENV_FILE: &str = ".env";
+
+/// Directory holding the data of the services
+const DATA_DIR: &str = "data";
+/// Directory holding the secrets entered by the user, like the RPC API key of a full node
+const SECRETS_DIR: &str = "madara/.secrets";
+
+/// Directories holding the rendered files, mirroring the layout of `deps`
+const DEPLOYMENT_DIRS: [&str; 7] = [
+    DATA_DIR,
+    "madara/configs/presets",
+    SECRETS_DIR,
+    "orchestrator",
+    "pathfinder",
+    "bootstrapper",
+    BOOTSTRAPPER_OUTPUT_DIR,
+];
+
+/// Entries of a deployment directory that are not rendered from the templates, kept by
+/// `clean`: the deployment itself, the data and secrets, the `.env` locating the data
+/// directories and what was recorded while deploying
+const NOT_RENDERED: [&str; 7] = [
+    DEPLOYMENT_FILE,
+    COMPOSE_ENV_FILE,
+    DATA_DIR,
+    SECRETS_DIR,
+    STATE_FILE,
+    ADDRESSES_FILE,
+    BOOTSTRAPPER_OUTPUT_DIR,
+];
+
+/// Content of `deployment.toml`
+#[derive(Debug, Serialize, Deserialize)]
+struct DeploymentInfo {
+    mode: MadaraMode,
+}
+
+/// A deployment started by `create`, identified by its name.
+///
+/// Every file rendered for the deployment (compose file, env files, runner scripts, presets)
+/// and its data directories live in `deployments/<name>`, or in the output directory of
+/// `--dry-run` when `create` renders it. The name is also the compose project
+/// name and the prefix of the container names, so several deployments can coexist.
+pub(crate) struct Deployment {
+    name: String,
+    mode: MadaraMode,
+    /// Directory holding the deployment directory
+    root: PathBuf,
+}
+
+impl Deployment {
+    /// Create the directory of a deployment, or reuse it if a deployment with the same name
+    /// and mode already exists
+    pub fn create(name: &str, mode: MadaraMode) -> anyhow::Result<Self> {
+        let deployment = Self {
+            name: normalize_name(name)?,
+            mode,
+            root: rendering_dir(),
+        };
+
+        let info_file = deployment.path(DEPLOYMENT_FILE);
+        if Path::new(&info_file).exists() {
+            let existing = Self::load_from(deployment.root.clone(), &deployment.name)?;
+            if existing.mode != mode {
+                bail!(
+                    "Deployment `{}` already exists as a {} deployment, use another --name or remove it with `stop {} --wipe`",
+                    deployment.name,
+                    existing.mode,
+                    deployment.name
+                );
+            }
+        }
+
+        for dir in DEPLOYMENT_DIRS {
+            fs::create_dir_all(deployment.path(dir))?;
+        }
+        fs::write(&info_file, toml::to_string(&DeploymentInfo { mode })?)?;
+
+        Ok(deployment)
+    }
+
+    /// Load an existing deployment of the workspace, even with `--dry-run`: the output
+    /// directory only holds what `create` rendered
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        Self::load_from(PathBuf::from(DEPLOYMENTS_DIR), name)
+    }
+
+    fn load_from(root: PathBuf, name: &str) -> anyhow::Result<Self> {
+        let name = normalize_name(name)?;
+        let info_file = root.join(&name).join(DEPLOYMENT_FILE);
+        if !info_file.exists() {
+            bail!(
+                "No deployment named `{}`, use `list` to see the existing ones",
+                name
+            );
+        }
+
+        let info: DeploymentInfo = toml::from_str(&fs::read_to_string(&info_file)?)
+            .with_context(|| format!("Invalid deployment file {}", info_file.display()))?;
+        Ok(Self {
+            name,
+            mode: info.mode,
+            root,
+        })
+    }
+
+    /// Every deployment of the workspace, sorted by name
+    pub fn all() -> anyhow::Result<Vec<Self>> {
+        let deployments_dir = PathBuf::from(DEPLOYMENTS_DIR);
+        if !deployments_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut names = vec![];
+        for entry in fs::read_dir(&deployments_dir)? {
+            let path = entry?.path();
+            if path.join(DEPLOYMENT_FILE).is_file() {
+                names.push(entry_name(&path));
+            }
+        }
+        names.sort();
+
+        names.iter().map(|name| Self::load(name)).collect()
+    }
+
+    /// Use the given deployment, or look for the only one with existing containers otherwise
+    pub fn resolve(name: Option<String>, shell: &Shell) -> anyhow::Result<Self> {
+        match name {
+            Some(name) => Self::load(&name),
+            None => Self::detect(shell),
+        }
+    }
+
+    /// Use the given deployment, or ask which one to use when there are several
+    pub fn select(name: Option<String>) -> anyhow::Result<Self> {
+        if let Some(name) = name {
+            return Self::load(&name);
+        }
+
+        let mut deployments = Self::all()?;
+        match deployments.len() {
+            0 => bail!("No deployment found, start one with `create`"),
+            1 => Ok(deployments.remove(0)),
+            _ => {
+                let names = deployments.iter().map(|d| d.name.clone());
+                let name = PromptSelect::new("Select the deployment:", names).ask();
+                Self::load(&name)
+            }
+        }
+    }
+
+    /// Find the deployment that has containers, if there's a single one
+    pub fn detect(shell: &Shell) -> anyhow::Result<Self> {
+        let mut deployments = Self::all()?;
+        if deployments.len() <= 1 {
+            return deployments
+                .pop()
+                .context("No deployment found, start one with `create`");
+        }
+
+        let names = deployments
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut with_containers = vec![];
+        for deployment in deployments {
+            if matches!(deployment.containers(shell), Ok(containers) if !containers.is_empty()) {
+                with_containers.push(deployment);
+            }
+        }
+
+        match with_containers.len() {
+            1 => Ok(with_containers.remove(0)),
+            _ => bail!(
+                "Several deployments found ({}), pass the name of the one to use",
+                names
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn mode(&self) -> MadaraMode {
+        self.mode
+    }
+
+    /// Directory of the deployment, relative to the workspace unless it's rendered with
+    /// `--dry-run`
+    pub fn dir(&self) -> String {
+        format!("{}/{}", self.root.display(), self.name)
+    }
+
+    /// Path of a file of the deployment, see [`Deployment::dir`]
+    pub fn path(&self, file: &str) -> String {
+        format!("{}/{}", self.dir(), file)
+    }
+
+    pub fn compose_file(&self) -> String {
+        self.path(COMPOSE_FILE)
+    }
+
+    /// Env file read by compose for the variables of the compose file
+    pub fn env_file(&self) -> String {
+        self.path(COMPOSE_ENV_FILE)
+    }
+
+    /// Location of `deps` seen from the deployment directory, used by the compose files to
+    /// mount the assets shared by every deployment. The directory must exist.
+    pub fn deps_dir(&self) -> anyhow::Result<String> {
+        let workspace = fs::canonicalize(std::env::current_dir()?)?;
+        let dir = fs::canonicalize(self.dir())?;
+        Ok(deps_dir_from(&workspace, &dir)
+            .to_string_lossy()
+            .to_string())
+    }
+
+    /// Containers of the deployment, empty if it was never started
+    pub fn containers(&self, shell: &Shell) -> anyhow::Result<Vec<ComposeContainer>> {
+        let compose_file = self.compose_file();
+        if !shell.path_exists(&compose_file) {
+            return Ok(vec![]);
+        }
+        runtime().ps(shell, &compose_file)
+    }
+
+    /// Host directories bind-mounted as data volumes, resolved relative to the compose file
+    pub fn data_dirs(&self, shell: &Shell) -> anyhow::Result<Vec<PathBuf>> {
+        let env_file = shell.current_dir().join(self.env_file());
+        if !env_file.exists() {
+            return Ok(vec![]);
+        }
+
+        let deployment_dir = shell.current_dir().join(self.dir());
+
+        let mut data_dirs = vec![];
+        for item in dotenvy::from_path_iter(&env_file)? {
+            let (key, value) = item?;
+            if DATA_DIR_VARIABLES.contains(&key.as_str()) {
+                data_dirs.push(deployment_dir.join(value));
+            }
+        }
+
+        Ok(data_dirs)
+    }
+
+    /// With `--output json`, list the files rendered into the deployment directory, leaving
+    /// out the data directories
+    pub fn emit_rendered_files(&self) -> anyhow::Result<()> {
+        if !output::is_json() {
+            return Ok(());
+        }
+
+        // Absolute paths, the events may be read from another directory than the workspace
+        let dir = std::env::current_dir()?.join(self.dir());
+        let mut files = vec![];
+        collect_files(&dir, &mut files)?;
+        let data_dir = dir.join(DATA_DIR).to_string_lossy().to_string();
+        files.retain(|file| !file.starts_with(&data_dir));
+        files.sort();
+
+        output::emit(Event::FilesRendered {
+            deployment: self.name.clone(),
+            files,
+        });
+        Ok(())
+    }
+
+    /// Files rendered from the templates into the deployment directory, see [`NOT_RENDERED`]
+    /// for what is left out
+    pub fn rendered_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let dir = PathBuf::from(self.dir());
+        let mut files = vec![];
+        collect_rendered(&dir, &dir, &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+
+    /// Remove the files rendered from the templates, `create` renders them again
+    pub fn remove_rendered_files(&self) -> anyhow::Result<()> {
+        for file in self.rendered_files()? {
+            fs::remove_file(file)?;
+        }
+        let dir = PathBuf::from(self.dir());
+        remove_empty_dirs(&dir, &dir)?;
+        Ok(())
+    }
+
+    /// Remove the directory of the deployment, with everything rendered into it
+    pub fn remove(self) -> anyhow::Result<()> {
+        fs::remove_dir_all(self.dir())?;
+        Ok(())
+    }
+}
+
+/// Directory `create` renders the deployments into, the output directory with `--dry-run`
+fn rendering_dir() -> PathBuf {
+    global_config()
+        .dry_run
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEPLOYMENTS_DIR))
+}
+
+/// Relative when the deployment is in the workspace, so the workspace can be moved, absolute
+/// otherwise, e.g. for an output directory of `--dry-run`
+pub(crate) fn deps_dir_from(workspace: &Path, deployment_dir: &Path) -> PathBuf {
+    match deployment_dir.strip_prefix(workspace) {
+        Ok(relative) => relative
+            .components()
+            .map(|_| Path::new(".."))
+            .collect::<PathBuf>()
+            .join(DEPS_REPO_PATH),
+        Err(_) => workspace.join(DEPS_REPO_PATH),
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<String>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path.to_string_lossy().to_string());
+        }
+    }
+    Ok(())
+}
+
+fn collect_rendered(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if is_not_rendered(root, &path) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_rendered(root, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_not_rendered(root: &Path, path: &Path) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    NOT_RENDERED.iter().any(|kept| relative == Path::new(kept))
+}
+
+// Remove the directories left empty under `dir`, `create` makes them again
+fn remove_empty_dirs(root: &Path, dir: &Path) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && !is_not_rendered(root, &path) {
+            remove_empty_dirs(root, &path)?;
+            if fs::read_dir(&path)?.next().is_none() {
+                fs::remove_dir(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Deployment names are used as directory and compose project names, which only accept
+/// lowercase letters, digits, `-` and `_`
+fn normalize_name(name: &str) -> anyhow::Result<String> {
+    let normalized = name.to_ascii_lowercase();
+    let valid_chars = normalized
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let valid_start = normalized
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+
+    if !valid_chars || !valid_start {
+        bail!(
+            "Invalid deployment name `{}`: use letters, digits, `-` and `_`, starting with a letter or a digit",
+            name
+        );
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Madara").unwrap(), "madara");
+        assert_eq!(normalize_name("app-chain_2").unwrap(), "app-chain_2");
+        assert!(normalize_name("").is_err());
+        assert!(normalize_name("-devnet").is_err());
+        assert!(normalize_name("../devnet").is_err());
+        assert!(normalize_name("my chain").is_err());
+    }
+
+    #[test]
+    fn test_deps_dir() {
+        let workspace = Path::new("/home/user/madara-cli");
+        assert_eq!(
+            deps_dir_from(workspace, &workspace.join("deployments/app-chain")),
+            Path::new("../../deps")
+        );
+        assert_eq!(
+            deps_dir_from(workspace, Path::new("/tmp/out/app-chain")),
+            workspace.join("deps")
+        );
+    }
+
+    #[test]
+    fn test_clean_keeps_what_is_not_rendered() {
+        let root = Path::new("deployments/app-chain");
+        assert!(is_not_rendered(root, &root.join("data")));
+        assert!(is_not_rendered(root, &root.join("madara/.secrets")));
+        assert!(is_not_rendered(root, &root.join("bootstrapper/output")));
+        assert!(!is_not_rendered(root, &root.join("compose.yaml")));
+        assert!(!is_not_rendered(
+            root,
+            &root.join("bootstrapper/devnet.json")
+        ));
+        assert!(!is_not_rendered(root, &root.join("madara/configs")));
+    }
+}
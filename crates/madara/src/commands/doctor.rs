@@ -0,0 +1,331 @@
+use std::{env, fmt, path::Path};
+
+use anyhow::bail;
+use madara_cli_common::{
+    config::global_config,
+    logger,
+    runtime::{runtime, RuntimeKind},
+};
+use xshell::{cmd, Shell};
+
+use crate::{
+    config::{global_config::Config, resources::LIMITED_SERVICES},
+    constants::{DEPLOYMENTS_DIR, DEPS_REPO_PATH},
+    workspace,
+};
+
+use super::{
+    deployment::Deployment,
+    madara::SINGLE_NODE_PORTS,
+    orchestrator::APPCHAIN_PORTS,
+    ports::{PortPlan, PortSpec},
+    resources::{self, format_bytes},
+};
+
+// Requirements from the README
+const MIN_DOCKER_VERSION: Version = Version(20, 10, 0);
+const MIN_COMPOSE_VERSION: Version = Version(2, 0, 0);
+// First version with `podman compose`, the compose provider has its own versions
+const MIN_PODMAN_VERSION: Version = Version(4, 7, 0);
+const MIN_CPUS: f64 = 4.0;
+const MIN_MEMORY: u64 = 8 * GB;
+const RECOMMENDED_DISK_SPACE: u64 = 50 * GB;
+// Below this, the nodes can't even sync for a few hours
+const MIN_DISK_SPACE: u64 = 5 * GB;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+enum Outcome {
+    Pass(String),
+    Warn(String),
+    Fail(String),
+}
+
+/// Check that the machine can run the deployments, printing a line per check. Fails if any
+/// check failed, warnings are only reported.
+pub(crate) fn run(shell: &Shell) -> anyhow::Result<()> {
+    logger::intro("Checking the environment");
+
+    // Limits and ports are checked with their defaults when the configuration is invalid
+    let global = global_config();
+    let config =
+        Config::resolve(global.config_file.as_deref(), &global.overrides).unwrap_or_default();
+
+    let kind = runtime().kind();
+    let checks = [
+        (kind.to_string(), check_runtime(shell, kind)),
+        (format!("{} daemon", kind), check_daemon(shell)),
+        (format!("{} Compose", kind), check_compose(shell, kind)),
+        ("Assets".to_string(), check_assets()),
+        ("CPU".to_string(), check_cpus(&config)),
+        ("Memory".to_string(), check_memory(shell, &config)),
+        ("Disk space".to_string(), check_disk_space(shell)),
+        ("Ports".to_string(), check_ports(&config)),
+    ];
+
+    let mut warnings = 0;
+    let mut failures = 0;
+    for (name, outcome) in checks {
+        match outcome {
+            Outcome::Pass(message) => logger::success(format!("{}: {}", name, message)),
+            Outcome::Warn(message) => {
+                warnings += 1;
+                logger::warn(format!("{}: {}", name, message));
+            }
+            Outcome::Fail(message) => {
+                failures += 1;
+                logger::error(format!("{}: {}", name, message));
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} check(s) failed", failures);
+    }
+    match warnings {
+        0 => logger::outro("Environment is ready"),
+        _ => logger::outro(format!(
+            "Environment is ready, with {} warning(s)",
+            warnings
+        )),
+    }
+    Ok(())
+}
+
+fn check_runtime(shell: &Shell, kind: RuntimeKind) -> Outcome {
+    let minimum = match kind {
+        RuntimeKind::Docker => MIN_DOCKER_VERSION,
+        RuntimeKind::Podman => MIN_PODMAN_VERSION,
+    };
+    match runtime().client_version(shell) {
+        Ok(version) => check_version(&version, minimum),
+        Err(e) => Outcome::Fail(format!("{} is not installed: {}", kind, e)),
+    }
+}
+
+fn check_daemon(shell: &Shell) -> Outcome {
+    match runtime().server_version(shell) {
+        Ok(version) => Outcome::Pass(format!("daemon {} is reachable", version)),
+        Err(e) => Outcome::Fail(format!("Unable to reach the daemon: {}", e)),
+    }
+}
+
+fn check_compose(shell: &Shell, kind: RuntimeKind) -> Outcome {
+    match (runtime().compose_version(shell), kind) {
+        (Ok(version), RuntimeKind::Docker) => check_version(&version, MIN_COMPOSE_VERSION),
+        (Ok(version), RuntimeKind::Podman) => Outcome::Pass(format!("version {}", version)),
+        (Err(_), RuntimeKind::Docker) => {
+            Outcome::Fail("The `docker compose` plugin is not installed".to_string())
+        }
+        (Err(e), RuntimeKind::Podman) => Outcome::Fail(format!(
+            "No compose provider found, install `docker-compose` or `podman-compose`: {}",
+            e
+        )),
+    }
+}
+
+fn check_version(version: &str, minimum: Version) -> Outcome {
+    match Version::parse(version) {
+        Some(parsed) if parsed >= minimum => Outcome::Pass(format!("version {}", version)),
+        Some(_) => Outcome::Fail(format!(
+            "version {} is too old, {} or higher is required",
+            version, minimum
+        )),
+        None => Outcome::Warn(format!("unable to parse version `{}`", version)),
+    }
+}
+
+fn check_assets() -> Outcome {
+    let missing = workspace::missing_assets(&global_config().workspace);
+    match missing.first() {
+        None => Outcome::Pass("every asset is present".to_string()),
+        Some(first) => Outcome::Fail(format!(
+            "{} file(s) missing, starting with {}. Remove {}/.assets-version to install them again",
+            missing.len(),
+            first,
+            DEPS_REPO_PATH
+        )),
+    }
+}
+
+fn check_cpus(config: &Config) -> Outcome {
+    let Some(cpus) = resources::host_cpus() else {
+        return Outcome::Warn("unable to read the number of CPUs".to_string());
+    };
+    let (requested, _) = requested_resources(config);
+
+    // Docker refuses to start a container with more CPUs than the machine has
+    if cpus < requested {
+        Outcome::Fail(format!(
+            "{} CPU(s) available, but services request up to {}. Lower them in [resources] or use --profile small",
+            cpus, requested
+        ))
+    } else if cpus < MIN_CPUS {
+        Outcome::Warn(format!(
+            "{} CPU(s) available, {} are recommended",
+            cpus, MIN_CPUS
+        ))
+    } else {
+        Outcome::Pass(format!("{} CPU(s) available", cpus))
+    }
+}
+
+fn check_memory(shell: &Shell, config: &Config) -> Outcome {
+    let Some(memory) = resources::host_memory(shell) else {
+        return Outcome::Warn("unable to read the total memory".to_string());
+    };
+    let (_, requested) = requested_resources(config);
+
+    if memory < MIN_MEMORY {
+        Outcome::Warn(format!(
+            "{} of memory, {} are recommended",
+            format_bytes(memory),
+            format_bytes(MIN_MEMORY)
+        ))
+    } else if memory < requested {
+        Outcome::Warn(format!(
+            "{} of memory, an app-chain may use up to {}. Lower the limits in [resources] or use --profile small",
+            format_bytes(memory),
+            format_bytes(requested)
+        ))
+    } else {
+        Outcome::Pass(format!("{} of memory", format_bytes(memory)))
+    }
+}
+
+fn check_disk_space(shell: &Shell) -> Outcome {
+    // Data directories live in the deployments, unless they were given as absolute paths
+    let mut dirs = vec![env::current_dir().unwrap_or_default().join(DEPLOYMENTS_DIR)];
+    for deployment in Deployment::all().unwrap_or_default() {
+        dirs.extend(deployment.data_dirs(shell).unwrap_or_default());
+    }
+
+    let mut lowest: Option<(u64, String)> = None;
+    for dir in dirs {
+        // The directory may not exist yet
+        let Some(existing) = dir.ancestors().find(|dir| dir.exists()) else {
+            continue;
+        };
+        let Some(available) = available_space(shell, existing) else {
+            continue;
+        };
+        match &lowest {
+            Some((lowest, _)) if *lowest <= available => {}
+            _ => lowest = Some((available, dir.display().to_string())),
+        }
+    }
+
+    let Some((available, dir)) = lowest else {
+        return Outcome::Warn("unable to read the free disk space".to_string());
+    };
+    let message = format!("{} free for {}", format_bytes(available), dir);
+    if available < MIN_DISK_SPACE {
+        Outcome::Fail(message)
+    } else if available < RECOMMENDED_DISK_SPACE {
+        Outcome::Warn(format!(
+            "{}, {} are recommended",
+            message,
+            format_bytes(RECOMMENDED_DISK_SPACE)
+        ))
+    } else {
+        Outcome::Pass(message)
+    }
+}
+
+fn check_ports(config: &Config) -> Outcome {
+    let ports = &config.ports;
+
+    let mut busy = vec![];
+    let plans: [&[PortSpec]; 2] = [&SINGLE_NODE_PORTS, &APPCHAIN_PORTS];
+    for specs in plans {
+        for mapping in PortPlan::new(specs, ports).busy() {
+            let port = format!("{} ({})", mapping.host_port, mapping.spec.service);
+            if !busy.contains(&port) {
+                busy.push(port);
+            }
+        }
+    }
+
+    if busy.is_empty() {
+        Outcome::Pass("every host port is free".to_string())
+    } else {
+        Outcome::Warn(format!(
+            "already in use: {}. Stop what uses them, change them in [ports] or use --auto-ports",
+            busy.join(", ")
+        ))
+    }
+}
+
+/// Highest CPU limit of a single service, and memory used by all the services of an
+/// app-chain, the largest deployment
+fn requested_resources(config: &Config) -> (f64, u64) {
+    let resources = &config.resources;
+    let cpus = LIMITED_SERVICES
+        .iter()
+        .map(|service| resources.limits(service).cpus)
+        .fold(0.0, f64::max);
+    let memory = LIMITED_SERVICES
+        .iter()
+        .map(|service| resources.limits(service).memory.bytes())
+        .sum();
+    (cpus, memory)
+}
+
+fn available_space(shell: &Shell, dir: &Path) -> Option<u64> {
+    // POSIX output: filesystem, size, used, available (in KB), capacity, mount point
+    let output = cmd!(shell, "df -Pk {dir}").quiet().read().ok()?;
+    let kb = output
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version(u32, u32, u32);
+
+impl Version {
+    /// Parse versions like `20.10.7`, `v2.29.1` or `2.29.1-desktop.1`
+    fn parse(version: &str) -> Option<Self> {
+        let mut parts = version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| {
+                let digits = part
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect::<String>();
+                digits.parse::<u32>().ok()
+            });
+
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(Version::parse("20.10.7"), Some(Version(20, 10, 7)));
+        assert_eq!(Version::parse("v2.29.1"), Some(Version(2, 29, 1)));
+        assert_eq!(Version::parse("2.29.1-desktop.1"), Some(Version(2, 29, 1)));
+        assert_eq!(Version::parse("27.1"), Some(Version(27, 1, 0)));
+        assert_eq!(Version::parse("unknown"), None);
+        assert!(Version(20, 9, 9) < MIN_DOCKER_VERSION);
+    }
+}
@@ -0,0 +1,85 @@
+use console::style;
+use madara_cli_common::{logger, output};
+use madara_cli_types::madara::MadaraMode;
+use serde::Serialize;
+use xshell::Shell;
+
+use super::{deployment::Deployment, status};
+
+/// A deployment with the number of its services that are running
+#[derive(Debug, Serialize)]
+pub(crate) struct DeploymentSummary {
+    pub name: String,
+    pub mode: MadaraMode,
+    pub directory: String,
+    /// `None` when the deployment was never started or the containers can't be listed
+    pub running: Option<usize>,
+    pub services: Option<usize>,
+}
+
+pub(crate) fn run(shell: &Shell) -> anyhow::Result<()> {
+    let summaries = Deployment::all()?
+        .iter()
+        .map(|deployment| summary(deployment, shell))
+        .collect::<Vec<_>>();
+
+    if output::is_json() {
+        return output::emit_table("deployments", &summaries);
+    }
+
+    if summaries.is_empty() {
+        logger::outro("No deployment found, start one with `create`");
+        return Ok(());
+    }
+
+    logger::note("Deployments", render_table(&summaries));
+    Ok(())
+}
+
+fn summary(deployment: &Deployment, shell: &Shell) -> DeploymentSummary {
+    let compose_file = deployment.compose_file();
+    let statuses = shell
+        .path_exists(&compose_file)
+        .then(|| status::collect(shell, &compose_file).ok())
+        .flatten();
+
+    DeploymentSummary {
+        name: deployment.name().to_string(),
+        mode: deployment.mode(),
+        directory: deployment.dir(),
+        running: statuses
+            .as_ref()
+            .map(|statuses| statuses.iter().filter(|s| s.state == "running").count()),
+        services: statuses.as_ref().map(Vec::len),
+    }
+}
+
+fn render_table(summaries: &[DeploymentSummary]) -> String {
+    let headers = ["NAME", "MODE", "RUNNING", "DIRECTORY"];
+    let rows: Vec<[String; 4]> = summaries
+        .iter()
+        .map(|s| {
+            [
+                s.name.clone(),
+                s.mode.to_string(),
+                match (s.running, s.services) {
+                    (Some(running), Some(services)) => format!("{}/{}", running, services),
+                    _ => "-".to_string(),
+                },
+                s.directory.clone(),
+            ]
+        })
+        .collect();
+
+    let lines = status::align_columns(&headers, &rows);
+    let mut table = style(&lines[0]).bold().to_string();
+    for (line, summary) in lines[1..].iter().zip(summaries) {
+        table.push('\n');
+        match summary.running {
+            Some(running) if running > 0 => table.push_str(line),
+            _ => table.push_str(&style(line).dim().to_string()),
+        }
+    }
+
+    table
+}
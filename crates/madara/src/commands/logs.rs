@@ -0,0 +1,13 @@
+use madara_cli_common::{docker::LogsOptions, logger, runtime::runtime};
+use xshell::Shell;
+
+use super::deployment::Deployment;
+
+pub(crate) fn run(name: Option<String>, options: LogsOptions, shell: &Shell) -> anyhow::Result<()> {
+    let deployment = Deployment::resolve(name, shell)?;
+    let compose_file = deployment.compose_file();
+
+    runtime().logs(shell, &compose_file, &options, &mut |source, message| {
+        logger::prefixed(source, message)
+    })
+}
@@ -1,80 +1,256 @@
-use std::fs::{self, create_dir_all, File, Permissions};
+use std::collections::BTreeMap;
+use std::fs::{self, File, Permissions};
 
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use clap::ValueEnum;
 
 use crate::config::global_config::Config;
 use crate::config::madara::MadaraPresetConfiguration;
-use crate::constants::{MADARA_COMPOSE_FILE, MADARA_DOCKER_IMAGE, MADARA_REPO_PATH};
+use crate::config::resources::ResourcesConfiguration;
+use crate::constants::{MADARA_DOCKER_IMAGE, MADARA_REPO_PATH};
 use crate::constants::{MADARA_RPC_API_KEY_FILE, MADARA_RUNNER_SCRIPT};
 
-use anyhow::anyhow;
-use cliclack::log;
-use madara_cli_common::{docker, Prompt};
+use madara_cli_common::{
+    config::global_config,
+    logger,
+    output::{self, Event},
+    runtime::runtime,
+    spinner::Spinner,
+    Prompt,
+};
 use madara_cli_config::madara::{
-    MadaraRunnerConfigAppChain, MadaraRunnerConfigFullNode, MadaraRunnerConfigMode,
-    MadaraRunnerConfigSequencer, MadaraRunnerParams,
+    MadaraPresetType, MadaraRunnerConfigAppChain, MadaraRunnerConfigFullNode,
+    MadaraRunnerConfigMode, MadaraRunnerConfigSequencer, MadaraRunnerParams,
 };
 use madara_cli_types::madara::{MadaraMode, MadaraNetwork};
+use minijinja::{context, Environment};
 use xshell::Shell;
 
-use super::{orchestrator, workspace_dir};
-
-const ENV_FILE_PATH: &str = "deps/madara/.env";
-const MADARA_CONFIG_FILE: &str = "deps/madara/configs/presets/devnet.yaml";
+use super::compose_override;
+use super::deployment::Deployment;
+use super::orchestrator;
+use super::ports::{PortPlan, PortSpec};
+use super::readiness::{self, ReadinessCheck};
+use super::resources;
+
+pub(crate) const MADARA_COMPOSE_TEMPLATE_FILE: &str = "compose.template";
+const MADARA_PRESETS_PATH: &str = "deps/madara/configs/presets";
+/// Path of the presets in the Madara container, relative to the runner script
+const CONTAINER_PRESETS_PATH: &str = "configs/presets";
+/// Preset rendered for the app-chain, from the one picked with `--chain-config-path` and the
+/// configuration of the chain, as seen by Madara
+const APPCHAIN_CHAIN_CONFIG: &str = "configs/presets/app-chain.yaml";
+/// [`APPCHAIN_CHAIN_CONFIG`] in the deployment directory
+pub(crate) const APPCHAIN_PRESET_FILE: &str = "madara/configs/presets/app-chain.yaml";
+// RPC port used by single-node deployments, as published by `deps/madara/compose.template`
+const MADARA_NODE_RPC_PORT: u16 = 9944;
+
+// Services of `deps/madara/compose.template` with CPU and memory limits
+const SINGLE_NODE_SERVICES: [&str; 1] = ["madara"];
+
+pub(crate) const SINGLE_NODE_PORTS: [PortSpec; 1] = [PortSpec {
+    key: "madara_rpc",
+    service: "madara",
+    container_port: MADARA_NODE_RPC_PORT,
+}];
 
 pub(crate) fn run(args: MadaraRunnerConfigMode, shell: &Shell) -> anyhow::Result<()> {
+    deploy(args, true, shell)?;
+    Ok(())
+}
+
+/// Render the files of a deployment without starting anything, printing the final compose
+/// file with `print`
+pub(crate) fn render(
+    args: MadaraRunnerConfigMode,
+    print: bool,
+    shell: &Shell,
+) -> anyhow::Result<()> {
+    let deployment = deploy(args, false, shell)?;
+    if print {
+        let content = fs::read_to_string(deployment.compose_file())?;
+        if output::is_json() {
+            output::emit(Event::Document {
+                name: "compose".to_string(),
+                content,
+            });
+        } else {
+            println!("{}", content);
+        }
+    }
+    logger::outro(format!(
+        "Deployment `{}` rendered into {}",
+        deployment.name(),
+        deployment.dir()
+    ));
+    Ok(())
+}
+
+fn deploy(args: MadaraRunnerConfigMode, start: bool, shell: &Shell) -> anyhow::Result<Deployment> {
     let mode = args.mode();
+    if mode != MadaraMode::AppChain && (args.resume || args.from_stage.is_some()) {
+        bail!("--resume and --from-stage only apply to app-chain deployments");
+    }
+    let deployment = Deployment::create(&args.deployment_name(), mode)?;
+    logger::info(format!(
+        "Rendering the {} deployment `{}` into {}",
+        mode,
+        deployment.name(),
+        deployment.dir()
+    ));
 
     match mode {
-        MadaraMode::AppChain => orchestrator::run(args, shell)?,
-        _ => {
-            madara_run(shell, args)?;
-        }
+        MadaraMode::AppChain => orchestrator::run(args, &deployment, start, shell)?,
+        _ => madara_run(shell, args, &deployment, start)?,
     };
 
-    Ok(())
+    if start && global_config().is_dry_run() {
+        logger::outro(format!(
+            "Dry run of `{}` done, nothing was started and the files were rendered into {}",
+            deployment.name(),
+            deployment.dir()
+        ));
+    }
+
+    Ok(deployment)
 }
 
 pub fn build_image(shell: &Shell) -> anyhow::Result<()> {
-    docker::build_image(
+    runtime().build_image(
         shell,
         MADARA_REPO_PATH.to_string(),
         MADARA_DOCKER_IMAGE.to_string(),
     )
 }
 
-fn madara_run(shell: &Shell, args: MadaraRunnerConfigMode) -> anyhow::Result<()> {
-    let config = Config::default();
-    process_params(&args, &config)?;
+fn madara_run(
+    shell: &Shell,
+    args: MadaraRunnerConfigMode,
+    deployment: &Deployment,
+    start: bool,
+) -> anyhow::Result<()> {
+    let global = global_config();
+    let mut config = Config::resolve(global.config_file.as_deref(), &global.overrides)?;
+    config.resources.profile = args.profile.or(config.resources.profile);
+    process_params(&args, &config, deployment)?;
     let mode = args.mode();
-    check_secrets(&args, mode)?;
+    check_secrets(&args, mode, deployment)?;
+
+    let mut ports = PortPlan::new(&SINGLE_NODE_PORTS, &config.ports);
+    // Nothing runs on this machine with --dry-run, so there's nothing to check
+    if start && !global.is_dry_run() {
+        ports.check(shell, deployment, args.auto_ports)?;
+        resources::check(shell, &config.resources, &SINGLE_NODE_SERVICES)?;
+    }
+    populate_compose(deployment, &ports, &config.resources)?;
+    compose_override::apply(deployment, &config.compose, &args.compose_overrides)?;
+    deployment.emit_rendered_files()?;
+    if !start {
+        return Ok(());
+    }
 
     // TODO: check if we need to run docker::down to remove any remaining previous instance
-    let compose_file = format!("{}/{}", MADARA_REPO_PATH, MADARA_COMPOSE_FILE);
-    docker::up(shell, &compose_file, false)
+    let compose_file = deployment.compose_file();
+    if !args.detach {
+        ports.print()?;
+        return runtime().up(shell, &compose_file, false);
+    }
+
+    let spinner = Spinner::new("Starting services...");
+    runtime().up(shell, &compose_file, true)?;
+    spinner.finish();
+    if global.is_dry_run() {
+        return Ok(());
+    }
+
+    let statuses = readiness::wait_until_ready(
+        shell,
+        &compose_file,
+        &[("madara", ReadinessCheck::Rpc(MADARA_NODE_RPC_PORT))],
+    )?;
+    // The block time of the shipped presets is well under the minimum timeout
+    let progress_timeout = readiness::block_progress_timeout(None);
+    readiness::verify_node(
+        &statuses,
+        "madara",
+        MADARA_NODE_RPC_PORT,
+        None,
+        Some(progress_timeout),
+    )?;
+    ports.print()?;
+    logger::outro(format!("Madara {} is ready", mode));
+
+    Ok(())
 }
 
-pub fn process_params(args: &MadaraRunnerConfigMode, config: &Config) -> anyhow::Result<()> {
+pub(crate) fn process_params(
+    args: &MadaraRunnerConfigMode,
+    config: &Config,
+    deployment: &Deployment,
+) -> anyhow::Result<()> {
     let mode = args.mode();
+    let name = args.node_name();
+    copy_presets(deployment)?;
 
     let runner_params = match &args.params {
-        MadaraRunnerParams::Devnet(_) => parse_devnet_params(&args.name, &mode),
-        MadaraRunnerParams::Sequencer(params) => parse_sequencer_params(&args.name, &mode, params),
-        MadaraRunnerParams::FullNode(params) => parse_full_node_params(&args.name, &mode, params),
-        MadaraRunnerParams::AppChain(params) => parse_appchain_params(params, config),
+        MadaraRunnerParams::Devnet(_) => parse_devnet_params(&name, &mode),
+        MadaraRunnerParams::Sequencer(params) => parse_sequencer_params(&name, &mode, params),
+        MadaraRunnerParams::FullNode(params) => parse_full_node_params(&name, &mode, params),
+        MadaraRunnerParams::AppChain(params) => parse_appchain_params(params, config, deployment),
     }?;
 
-    write_env_file(args)?;
-    let runner_script_path = workspace_dir()
-        .join(MADARA_REPO_PATH)
-        .join(MADARA_RUNNER_SCRIPT);
+    write_env_file(args, deployment)?;
+    let runner_script_path =
+        PathBuf::from(deployment.path(&format!("madara/{}", MADARA_RUNNER_SCRIPT)));
     create_runner_script(mode, runner_params, &runner_script_path)?;
 
     Ok(())
 }
 
+/// Copy the chain presets into the deployment, where the app-chain preset is updated with the
+/// configuration of the chain
+fn copy_presets(deployment: &Deployment) -> anyhow::Result<()> {
+    let presets_dir = deployment.path("madara/configs/presets");
+    for entry in fs::read_dir(MADARA_PRESETS_PATH)? {
+        let path = entry?.path();
+        if let Some(file_name) = path.file_name() {
+            fs::copy(&path, PathBuf::from(&presets_dir).join(file_name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Render the compose file of single-node deployments
+fn populate_compose(
+    deployment: &Deployment,
+    ports: &PortPlan,
+    resources: &ResourcesConfiguration,
+) -> anyhow::Result<()> {
+    let compose_template = format!("{}/{}", MADARA_REPO_PATH, MADARA_COMPOSE_TEMPLATE_FILE);
+    let template = fs::read_to_string(compose_template)?;
+
+    let mut env = Environment::new();
+    env.add_template("compose_template", &template)?;
+    let data = context! {
+        PROJECT_NAME => deployment.name(),
+        DEPS_DIR => deployment.deps_dir()?,
+        PORTS => ports.host_ports(),
+        RESOURCES => SINGLE_NODE_SERVICES
+            .map(|service| (service, resources.limits(service)))
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    };
+
+    let rendered = env.get_template("compose_template")?.render(&data)?;
+    fs::write(deployment.compose_file(), rendered)?;
+
+    Ok(())
+}
+
 /// This will receive the necessary params to launch Madara and it'll overwrite `madara-runner.sh`,
 /// so it can be used by docker-compose file to spin up the node
 fn create_runner_script(
@@ -111,7 +287,7 @@ fn create_runner_script(
     }
 
     // Dump config into madara-runner script
-    let mut file = File::create(&path)?;
+    let mut file = File::create(path)?;
     file.write_all(script.as_bytes())?;
 
     // Set execuion permission
@@ -121,36 +297,27 @@ fn create_runner_script(
     Ok(())
 }
 
-fn check_secrets(args: &MadaraRunnerConfigMode, mode: MadaraMode) -> anyhow::Result<()> {
+fn check_secrets(
+    args: &MadaraRunnerConfigMode,
+    mode: MadaraMode,
+    deployment: &Deployment,
+) -> anyhow::Result<()> {
+    // The `.secrets` directory is created with the deployment
+    let rpc_api_secret =
+        PathBuf::from(deployment.path(&format!("madara/{}", MADARA_RPC_API_KEY_FILE)));
+
     match mode {
         MadaraMode::FullNode => {
-            let rpc_api_secret = PathBuf::new()
-                .join(MADARA_REPO_PATH)
-                .join(MADARA_RPC_API_KEY_FILE);
-
             let full_node_params = match &args.params {
                 MadaraRunnerParams::FullNode(params) => params,
                 _ => unreachable!("We already know it's full node!"),
             };
 
-            // Create .secrets and missing folders
-            if let Some(parent) = rpc_api_secret.parent() {
-                if !parent.exists() {
-                    log::info(format!(
-                        "Creating missing directories: {}",
-                        parent.display()
-                    ))?;
-                    create_dir_all(parent).map_err(|e| {
-                        anyhow!("Failed to create directories {}: {}", parent.display(), e)
-                    })?;
-                }
-            }
-
             if let Some(rpc_api_url) = &full_node_params.rpc_api_url {
                 fs::write(rpc_api_secret, rpc_api_url)?;
             } else if !rpc_api_secret.exists() {
                 let rpc_api_url: String = Prompt::new("Input RPC_API url:").ask();
-                log::info(format!("Creating file: {}", rpc_api_secret.display()))?;
+                logger::info(format!("Creating file: {}", rpc_api_secret.display()));
                 fs::write(rpc_api_secret, rpc_api_url)?;
             } else {
                 let rpc_api_url = fs::read_to_string(&rpc_api_secret)?;
@@ -161,24 +328,8 @@ fn check_secrets(args: &MadaraRunnerConfigMode, mode: MadaraMode) -> anyhow::Res
             }
         }
         MadaraMode::Devnet | MadaraMode::Sequencer => {
-            let rpc_api_secret = PathBuf::new()
-                .join(MADARA_REPO_PATH)
-                .join(MADARA_RPC_API_KEY_FILE);
-
-            // Create .secrets and missing folders
-            if let Some(parent) = rpc_api_secret.parent() {
-                if !parent.exists() {
-                    log::info(format!(
-                        "Creating missing directories: {}",
-                        parent.display()
-                    ))?;
-                    create_dir_all(parent).map_err(|e| {
-                        anyhow!("Failed to create directories {}: {}", parent.display(), e)
-                    })?;
-                }
-            }
             if !rpc_api_secret.exists() {
-                log::info(format!("Creating file: {}", rpc_api_secret.display()))?;
+                logger::info(format!("Creating file: {}", rpc_api_secret.display()));
                 fs::write(rpc_api_secret, "")?;
             }
         }
@@ -187,15 +338,24 @@ fn check_secrets(args: &MadaraRunnerConfigMode, mode: MadaraMode) -> anyhow::Res
     Ok(())
 }
 
-fn write_env_file(args: &MadaraRunnerConfigMode) -> anyhow::Result<()> {
+fn write_env_file(args: &MadaraRunnerConfigMode, deployment: &Deployment) -> anyhow::Result<()> {
     let db_folder = match &args.params {
         MadaraRunnerParams::Devnet(params) => params.base_path.clone(),
         MadaraRunnerParams::FullNode(params) => params.base_path.clone(),
         MadaraRunnerParams::Sequencer(params) => params.base_path.clone(),
         MadaraRunnerParams::AppChain(_) => return Ok(()),
     };
+    // Compose treats a volume source that is not a path as a named volume
+    let db_folder = if db_folder.starts_with('.') || db_folder.starts_with('/') {
+        db_folder
+    } else {
+        format!("./{}", db_folder)
+    };
 
-    fs::write(ENV_FILE_PATH, format!("MADARA_DATA_DIR={}", db_folder))?;
+    fs::write(
+        deployment.env_file(),
+        format!("MADARA_DATA_DIR={}", db_folder),
+    )?;
 
     Ok(())
 }
@@ -234,7 +394,7 @@ fn parse_sequencer_params(
         "--gateway-enable".to_string(),
         "--gateway-external".to_string(),
         "--rpc-external".to_string(),
-        "--rpc-port 9945".to_string(),
+        format!("--rpc-port {}", MADARA_NODE_RPC_PORT),
         "--rpc-cors \"*\"".to_string(),
         "--gas-price 10".to_string(),
         "--blob-gas-price 20".to_string(),
@@ -271,14 +431,12 @@ fn parse_full_node_params(
 fn parse_appchain_params(
     params: &MadaraRunnerConfigAppChain,
     config: &Config,
+    deployment: &Deployment,
 ) -> anyhow::Result<Vec<String>> {
-    // TODO: this file and MADARA_CONFIG_FILE must be the same.
-    // Hardcoded to devnet.yaml at the moment
-    let chain_config_path = &params.chain_config_path;
-
-    // Update devnet preset with global config:
+    // Apply the configuration of the chain to the picked preset, Madara runs with the result
     let global_config = config.clone();
-    let mut preset = MadaraPresetConfiguration::load(MADARA_CONFIG_FILE);
+    let source = appchain_preset_source(&params.chain_config_path)?;
+    let mut preset = MadaraPresetConfiguration::load(&source.to_string_lossy())?;
     preset.chain_name = global_config.madara.chain_name;
     preset.chain_id = global_config.madara.app_chain_id;
     preset.eth_gps_statement_verifier = global_config.l1_config.verifier_address;
@@ -287,13 +445,13 @@ fn parse_appchain_params(
     preset.pending_block_update_time = global_config.madara.pending_block_update_time;
     preset.native_fee_token_address = global_config.madara.native_fee_token_address;
     preset.parent_fee_token_address = global_config.madara.parent_fee_token_address;
-    preset.save(MADARA_CONFIG_FILE);
+    preset.save(&deployment.path(APPCHAIN_PRESET_FILE))?;
 
     let appchain_params = vec![
         format!("--name {}", config.madara.chain_name),
         "--sequencer".to_string(),
         "--base-path /usr/share/madara/data".to_string(),
-        format!("--chain-config-path {}", chain_config_path),
+        format!("--chain-config-path {}", APPCHAIN_CHAIN_CONFIG),
         "--feeder-gateway-enable".to_string(),
         "--gateway-enable".to_string(),
         "--gateway-external".to_string(),
@@ -311,3 +469,88 @@ fn parse_appchain_params(
 
     Ok(appchain_params)
 }
+
+/// Preset picked with `--chain-config-path`: one of the presets of `deps`, by name or by its
+/// path in the container, or the path of a custom preset file
+fn appchain_preset_source(chain_config_path: &str) -> anyhow::Result<PathBuf> {
+    if let Ok(preset) = MadaraPresetType::from_str(chain_config_path, true) {
+        let Some(name) = preset_file_name(preset) else {
+            bail!("Pass the path of the custom preset file with --chain-config-path");
+        };
+        return Ok(Path::new(MADARA_PRESETS_PATH).join(name));
+    }
+
+    // e.g. `configs/presets/devnet.yaml`
+    let shipped = chain_config_path
+        .strip_prefix(CONTAINER_PRESETS_PATH)
+        .map(|file| Path::new(MADARA_PRESETS_PATH).join(file.trim_start_matches('/')))
+        .filter(|path| path.is_file());
+    if let Some(shipped) = shipped {
+        return Ok(shipped);
+    }
+
+    let custom = PathBuf::from(chain_config_path);
+    if custom.is_file() {
+        return Ok(custom);
+    }
+    bail!(
+        "Chain config `{}` not found, use one of {} or the path of a preset file",
+        chain_config_path,
+        MadaraPresetType::value_variants()
+            .iter()
+            .filter_map(|preset| preset_file_name(*preset))
+            .map(|name| name.trim_end_matches(".yaml").to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn preset_file_name(preset: MadaraPresetType) -> Option<String> {
+    match preset {
+        MadaraPresetType::Custom => None,
+        preset => preset
+            .to_possible_value()
+            .map(|value| format!("{}.yaml", value.get_name())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appchain_preset_source() {
+        let presets = Path::new(MADARA_PRESETS_PATH);
+        assert_eq!(
+            appchain_preset_source("sepolia").unwrap(),
+            presets.join("sepolia.yaml")
+        );
+        assert_eq!(
+            appchain_preset_source("Devnet").unwrap(),
+            presets.join("devnet.yaml")
+        );
+        assert!(appchain_preset_source("custom").is_err());
+        assert!(appchain_preset_source("configs/presets/missing.yaml").is_err());
+
+        // Keys the CLI doesn't set are kept in the rendered preset
+        for preset in MadaraPresetType::value_variants() {
+            let Some(name) = preset_file_name(*preset) else {
+                continue;
+            };
+            let file = Path::new("../..").join(presets).join(&name);
+            let source = fs::read_to_string(&file).unwrap();
+            let loaded = MadaraPresetConfiguration::load(&file.to_string_lossy()).unwrap();
+            let rendered: serde_yaml::Value =
+                serde_yaml::from_str(&serde_yaml::to_string(&loaded).unwrap()).unwrap();
+            let source: serde_yaml::Value = serde_yaml::from_str(&source).unwrap();
+            for key in source.as_mapping().unwrap().keys() {
+                assert!(
+                    rendered.get(key).is_some(),
+                    "{:?} dropped from {}",
+                    key,
+                    name
+                );
+            }
+        }
+    }
+}
@@ -1,20 +1,21 @@
-use std::path::{Path, PathBuf};
-
+pub mod addresses;
 pub mod anvil;
+pub mod appchain;
 pub mod bootstrapper;
+pub mod clean;
+pub mod compose_override;
+pub mod config;
+pub mod deployment;
+pub mod doctor;
+pub mod list;
+pub mod logs;
 pub mod madara;
 pub mod orchestrator;
 pub mod os;
 pub mod pathfinder;
-
-pub(crate) fn workspace_dir() -> PathBuf {
-    let output = std::process::Command::new(env!("CARGO"))
-        .arg("locate-project")
-        .arg("--workspace")
-        .arg("--message-format=plain")
-        .output()
-        .unwrap()
-        .stdout;
-    let cargo_path = Path::new(std::str::from_utf8(&output).unwrap().trim());
-    cargo_path.parent().unwrap().to_path_buf()
-}
+pub mod pipeline;
+pub mod ports;
+pub mod readiness;
+pub mod resources;
+pub mod status;
+pub mod stop;
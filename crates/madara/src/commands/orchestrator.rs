@@ -1,6 +1,9 @@
-use madara_cli_common::{config::global_config, docker, logger, spinner::Spinner};
+use madara_cli_common::{
+    config::global_config, docker::LogsOptions, logger, runtime::runtime, spinner::Spinner,
+};
 use madara_cli_config::{
     bootstrapper::BootstrapperConfig,
+    compose::Compose,
     madara::MadaraRunnerConfigMode,
     madara::MadaraRunnerParams,
     prover::{ProverRunnerConfig, ProverType},
@@ -8,13 +11,18 @@ use madara_cli_config::{
 use xshell::Shell;
 
 use crate::{
-    commands,
-    config::global_config::Config,
-    constants::{
-        DEFAULT_LOCAL_CONFIG_FILE, DEPS_REPO_PATH, DOCKERHUB_ORGANIZATION,
-        REMOTE_BOOTSTRAPPER_IMAGE, REMOTE_HELPER_IMAGE, REMOTE_MADARA_IMAGE,
-        REMOTE_ORCHESTRATOR_IMAGE, REMOTE_PATHFINDER_IMAGE,
+    commands::{
+        self,
+        addresses::DeploymentAddresses,
+        appchain::AppChainCompose,
+        compose_override,
+        deployment::Deployment,
+        pipeline::{self, Start},
+        ports::{PortPlan, PortSpec},
+        readiness::{self, ReadinessCheck},
+        resources,
     },
+    config::{global_config::Config, resources::LIMITED_SERVICES},
 };
 
 use dotenvy::from_filename;
@@ -26,69 +34,212 @@ use std::os::unix::fs::PermissionsExt;
 
 const ORCHESTRATOR_REPO_PATH: &str = "deps/orchestrator";
 const ORCHESTRATOR_DOCKER_IMAGE: &str = "orchestrator";
-const ORCHESTRATOR_COMPOSE_TEMPLATE_FILE: &str = "compose.template";
-const ORCHESTRATOR_COMPOSE_FILE: &str = "compose.yaml";
 const ORCHESTRATOR_ENV_TEMPLATE_FILE: &str = ".env.template";
-const ORCHESTRATOR_ENV_PATH: &str = "deps/orchestrator/.env";
+const ORCHESTRATOR_ENV_FILE: &str = "orchestrator/.env";
 const ORCHESTRATOR_RUNNER_TEMPLATE_FILE: &str = "run_orchestrator.template";
-const ORCHESTRATOR_RUNNER_FILE: &str = "run_orchestrator.sh";
-const COMPOSE_ENV_FILE: &str = "deps/.env";
-
-pub(crate) fn init() -> anyhow::Result<()> {
+const ORCHESTRATOR_RUNNER_FILE: &str = "orchestrator/run_orchestrator.sh";
+
+pub(crate) const MADARA_RPC_PORT: u16 = 9945;
+pub(crate) const PATHFINDER_RPC_PORT: u16 = 9545;
+const PROVER_PORT_KEY: &str = "prover";
+
+// Ports published by the app-chain compose file
+pub(crate) const APPCHAIN_PORTS: [PortSpec; 7] = [
+    PortSpec {
+        key: "anvil_rpc",
+        service: "anvil",
+        container_port: 8545,
+    },
+    PortSpec {
+        key: "madara_rpc",
+        service: "madara",
+        container_port: MADARA_RPC_PORT,
+    },
+    PortSpec {
+        key: "madara_gateway",
+        service: "madara",
+        container_port: 8080,
+    },
+    PortSpec {
+        key: "pathfinder_rpc",
+        service: "pathfinder",
+        container_port: PATHFINDER_RPC_PORT,
+    },
+    PortSpec {
+        key: "mongodb",
+        service: "mongodb",
+        container_port: 27017,
+    },
+    PortSpec {
+        key: "localstack",
+        service: "localstack",
+        container_port: 4566,
+    },
+    PortSpec {
+        key: PROVER_PORT_KEY,
+        service: "prover",
+        container_port: 6000,
+    },
+];
+
+// One-shot jobs must complete, Madara and Pathfinder must answer on their RPC port
+const APPCHAIN_READINESS_CHECKS: [(&str, ReadinessCheck); 7] = [
+    ("mock_verifier_contract", ReadinessCheck::Completed),
+    ("bootstrapper_l1", ReadinessCheck::Completed),
+    ("bootstrapper_l2", ReadinessCheck::Completed),
+    ("override_state", ReadinessCheck::Completed),
+    ("init_mongo", ReadinessCheck::Completed),
+    ("madara", ReadinessCheck::Rpc(MADARA_RPC_PORT)),
+    ("pathfinder", ReadinessCheck::Rpc(PATHFINDER_RPC_PORT)),
+];
+
+pub(crate) fn init(from: Option<String>) -> anyhow::Result<()> {
     let use_default = global_config().default;
-    Config::init(use_default)?;
+    Config::init(use_default, from.as_deref())?;
     Ok(())
 }
 
-pub(crate) fn run(args_madara: MadaraRunnerConfigMode, shell: &Shell) -> anyhow::Result<()> {
+pub(crate) fn run(
+    args_madara: MadaraRunnerConfigMode,
+    deployment: &Deployment,
+    start: bool,
+    shell: &Shell,
+) -> anyhow::Result<()> {
     logger::new_empty_line();
     logger::intro("Madara CLI");
 
-    // TODO: improve error handling and check if file doesn't exist (even default file)
-    let config_file = global_config()
-        .config_file
-        .as_ref()
-        .map(|s| s.clone())
-        .unwrap_or_else(|| DEFAULT_LOCAL_CONFIG_FILE.to_string());
-    let config = Config::load(&config_file);
+    let global = global_config();
+    let mut config = Config::resolve(global.config_file.as_deref(), &global.overrides)?;
+    config.resources.profile = args_madara.profile.or(config.resources.profile);
+    config.validate()?;
 
     // Collect Madara configuration
-    commands::madara::process_params(&args_madara, &config)?;
+    commands::madara::process_params(&args_madara, &config, deployment)?;
 
     let args = match args_madara.params {
         MadaraRunnerParams::AppChain(args) => args,
         _ => unreachable!("AppChain config expected"),
     };
 
-    commands::bootstrapper::process_params(&config)?;
+    commands::bootstrapper::process_params(&config, deployment)?;
 
     // Collect Pathfinder configuration
-    commands::pathfinder::parse_params(&args.pathfinder_config, &config)?;
+    commands::pathfinder::parse_params(&args.pathfinder_config, &config, deployment)?;
 
-    // Read and load the env variables from deps/orchestrator/.env if the file was created.
-    // On the first run, fallback to `ATLANTIC_API` to give the user a hint about what is needed in that field
-    let _ = from_filename(ORCHESTRATOR_ENV_PATH.to_string());
+    // Read and load the env variables from the orchestrator/.env of the deployment if the file
+    // was created. On the first run, fallback to `ATLANTIC_API` to give the user a hint about
+    // what is needed in that field
+    let _ = from_filename(deployment.path(ORCHESTRATOR_ENV_FILE));
 
     // Collect Prover configuration
     let args_prover = &args.prover_config;
-    populate_orchestrator_env(args_prover, &config)?;
-    populate_orchestrator_runner(args_prover)?;
-    populate_orchestrator_compose(args_prover, &args.bootstrapper_config, &config)?;
+    populate_orchestrator_env(args_prover, &config, deployment)?;
+    populate_orchestrator_runner(args_prover, deployment)?;
+
+    // The mock prover is only deployed with the dummy prover
+    let port_specs = APPCHAIN_PORTS
+        .into_iter()
+        .filter(|spec| spec.key != PROVER_PORT_KEY || args_prover.prover_type == ProverType::Dummy)
+        .collect::<Vec<_>>();
+    let mut ports = PortPlan::new(&port_specs, &config.ports);
+    // Nothing runs on this machine with --dry-run, so there's nothing to check
+    if start && !global.is_dry_run() {
+        ports.check(shell, deployment, args_madara.auto_ports)?;
+        resources::check(shell, &config.resources, &LIMITED_SERVICES)?;
+    }
+    populate_orchestrator_compose(
+        args_prover,
+        &args.bootstrapper_config,
+        &config,
+        deployment,
+        &ports,
+    )?;
+    // Rendering again resets the files to the template addresses, the ones reported by the
+    // bootstrapper are only collected again when its stages run
+    if let Some(addresses) = DeploymentAddresses::load(deployment)? {
+        addresses.apply(deployment)?;
+    }
+    compose_override::apply(deployment, &config.compose, &args_madara.compose_overrides)?;
+    deployment.emit_rendered_files()?;
+    if !start {
+        return Ok(());
+    }
 
     // Build all images
     if args_prover.build_images {
         build_images(shell)?;
     }
 
-    // Spin up all the necessary services
-    run_orchestrator(&shell)?;
+    // Spin up all the necessary services, stage by stage
+    let start = Start::new(args_madara.resume, args_madara.from_stage);
+    run_orchestrator(
+        shell,
+        args_madara.detach,
+        start,
+        &config,
+        deployment,
+        &ports,
+    )?;
 
     Ok(())
 }
 
-fn run_orchestrator(shell: &Shell) -> anyhow::Result<()> {
-    let compose_file = format!("{}/{}", DEPS_REPO_PATH, ORCHESTRATOR_COMPOSE_FILE);
-    docker::up(shell, &compose_file, false)
+fn run_orchestrator(
+    shell: &Shell,
+    detach: bool,
+    start: Start,
+    config: &Config,
+    deployment: &Deployment,
+    ports: &PortPlan,
+) -> anyhow::Result<()> {
+    let compose_file = deployment.compose_file();
+    pipeline::run(shell, deployment, start)?;
+    if global_config().is_dry_run() {
+        return Ok(());
+    }
+
+    if !detach {
+        ports.print()?;
+        logger::info(format!(
+            "Every stage finished, following the logs. The services keep running after Ctrl+C, use `stop {}` to tear them down",
+            deployment.name()
+        ));
+        return follow_logs(shell, &compose_file);
+    }
+
+    let statuses = readiness::wait_until_ready(shell, &compose_file, &APPCHAIN_READINESS_CHECKS)?;
+    let chain_id = Some(config.madara.app_chain_id.as_str());
+    let progress_timeout = readiness::block_progress_timeout(config.madara.block_duration());
+    readiness::verify_node(
+        &statuses,
+        "madara",
+        MADARA_RPC_PORT,
+        chain_id,
+        Some(progress_timeout),
+    )?;
+    readiness::verify_node(&statuses, "pathfinder", PATHFINDER_RPC_PORT, chain_id, None)?;
+    ports.print()?;
+    logger::outro("App-chain is ready");
+
+    Ok(())
+}
+
+/// Follow the logs of the services that were attached when running in the foreground
+fn follow_logs(shell: &Shell, compose_file: &str) -> anyhow::Result<()> {
+    let compose = Compose::load(compose_file)?;
+    let options = LogsOptions {
+        services: compose
+            .services
+            .iter()
+            .filter(|(_, service)| service.attach != Some(false))
+            .map(|(name, _)| name.clone())
+            .collect(),
+        follow: true,
+        ..Default::default()
+    };
+    runtime().logs(shell, compose_file, &options, &mut |source, message| {
+        logger::prefixed(source, message)
+    })
 }
 
 fn build_images(shell: &Shell) -> anyhow::Result<()> {
@@ -112,7 +263,7 @@ fn build_images(shell: &Shell) -> anyhow::Result<()> {
 }
 
 fn build_image(shell: &Shell) -> anyhow::Result<()> {
-    docker::build_image(
+    runtime().build_image(
         shell,
         ORCHESTRATOR_REPO_PATH.to_string(),
         ORCHESTRATOR_DOCKER_IMAGE.to_string(),
@@ -124,12 +275,13 @@ fn build_image(shell: &Shell) -> anyhow::Result<()> {
 fn populate_orchestrator_env(
     prover_config: &ProverRunnerConfig,
     config: &Config,
+    deployment: &Deployment,
 ) -> anyhow::Result<()> {
     let env_template = format!(
         "{}/{}",
         ORCHESTRATOR_REPO_PATH, ORCHESTRATOR_ENV_TEMPLATE_FILE
     );
-    let env_output = ORCHESTRATOR_ENV_PATH.to_string();
+    let env_output = deployment.path(ORCHESTRATOR_ENV_FILE);
 
     // Read the template file
     let template = fs::read_to_string(env_template).expect("Failed to read .env.template");
@@ -155,12 +307,15 @@ fn populate_orchestrator_env(
     Ok(())
 }
 
-fn populate_orchestrator_runner(prover_config: &ProverRunnerConfig) -> anyhow::Result<()> {
+fn populate_orchestrator_runner(
+    prover_config: &ProverRunnerConfig,
+    deployment: &Deployment,
+) -> anyhow::Result<()> {
     let runner_template = format!(
         "{}/{}",
         ORCHESTRATOR_REPO_PATH, ORCHESTRATOR_RUNNER_TEMPLATE_FILE
     );
-    let runner_output = format!("{}/{}", ORCHESTRATOR_REPO_PATH, ORCHESTRATOR_RUNNER_FILE);
+    let runner_output = deployment.path(ORCHESTRATOR_RUNNER_FILE);
 
     // Read the template file
     let template =
@@ -196,70 +351,29 @@ fn populate_orchestrator_compose(
     prover_config: &ProverRunnerConfig,
     bootstrapper_config: &BootstrapperConfig,
     config: &Config,
+    deployment: &Deployment,
+    ports: &PortPlan,
 ) -> anyhow::Result<()> {
-    let compose_template = format!("{}/{}", DEPS_REPO_PATH, ORCHESTRATOR_COMPOSE_TEMPLATE_FILE);
-    let compose_output = format!("{}/{}", DEPS_REPO_PATH, ORCHESTRATOR_COMPOSE_FILE);
-
-    // Read the template file
-    let template = fs::read_to_string(compose_template).expect("Failed to read compose.template");
-
-    // Set up MiniJinja
-    let mut env = Environment::new();
-    env.add_template("compose_template", &template)
-        .expect("Failed to add template");
-
-    // Call correct image version depending if it's building locally
-    let (
-        repo,
-        helper_version,
-        bootstrapper_version,
-        madara_version,
-        pathfinder_version,
-        orchestrator_version,
-    ) = if prover_config.build_images {
-        ("", "latest", "latest", "latest", "latest", "latest")
-    } else {
-        (
-            DOCKERHUB_ORGANIZATION,
-            REMOTE_HELPER_IMAGE,
-            REMOTE_BOOTSTRAPPER_IMAGE,
-            REMOTE_MADARA_IMAGE,
-            REMOTE_PATHFINDER_IMAGE,
-            REMOTE_ORCHESTRATOR_IMAGE,
-        )
-    };
-
-    let data = context! {
-        ENABLE_DUMMY_PROVER => prover_config.prover_type == ProverType::Dummy,
-        ENABLE_BOOTSTRAPER_L2_SETUP => bootstrapper_config.deploy_l2_contracts,
-        IMAGE_REPOSITORY => repo,
-        ETH_PRIV_KEY => config.eth_wallet.eth_priv_key,
-    };
-
-    // Render the template
-    let tmpl = env.get_template("compose_template").unwrap();
-    let rendered = tmpl.render(&data).expect("Template rendering failed");
-    fs::write(compose_output, rendered).expect("Failed to write compose.yaml");
+    let deps_dir = deployment.deps_dir()?;
+    let compose = AppChainCompose {
+        project_name: deployment.name(),
+        eth_priv_key: &config.eth_wallet.eth_priv_key,
+        deps_dir: &deps_dir,
+        ports: ports.host_ports(),
+        resources: &config.resources,
+        local_images: prover_config.build_images,
+        dummy_prover: prover_config.prover_type == ProverType::Dummy,
+        deploy_l2_contracts: bootstrapper_config.deploy_l2_contracts,
+    }
+    .build();
+    compose.save(&deployment.compose_file())?;
 
-    // Write the env file for the orchestrator
+    // Data directories are read back from the env file by `stop --wipe`
     fs::write(
-        COMPOSE_ENV_FILE,
-        format!(
-            "
-            HELPER_VERSION={}\n
-            BOOTSTRAPPER_VERSION={}\n
-            MADARA_VERSION={}\n
-            PATHFINDER_VERSION={}\n
-            ORCHESTRATOR_VERSION={}\n
-            ANVIL_DATA_DIR=./data/anvil
-            PATHFINDER_DATA_DIR=./data/pathfinder\n
-            MADARA_DATA_DIR=./data/madara",
-            helper_version,
-            bootstrapper_version,
-            madara_version,
-            pathfinder_version,
-            orchestrator_version
-        ),
+        deployment.env_file(),
+        "ANVIL_DATA_DIR=./data/anvil\n\
+         PATHFINDER_DATA_DIR=./data/pathfinder\n\
+         MADARA_DATA_DIR=./data/madara\n",
     )?;
 
     Ok(())
@@ -1,4 +1,4 @@
-use madara_cli_common::{docker, spinner::Spinner};
+use madara_cli_common::{runtime::runtime, spinner::Spinner};
 use xshell::Shell;
 
 use crate::constants::MSG_BUILDING_IMAGE_SPINNER;
@@ -12,7 +12,7 @@ pub fn build_os(shell: &Shell, rebuild: bool) -> anyhow::Result<()> {
     // TODO: Check if OS file is present. If not, build image and copy OS anyways
     if rebuild {
         let spinner = Spinner::new(MSG_BUILDING_IMAGE_SPINNER);
-        docker::build_image(
+        runtime().build_image(
             shell,
             CAIRO_LANG_REPO_PATH.to_string(),
             CAIRO_LANG_DOCKER_IMAGE.to_string(),
@@ -20,7 +20,7 @@ pub fn build_os(shell: &Shell, rebuild: bool) -> anyhow::Result<()> {
         spinner.finish();
 
         let compose_file = format!("{}/{}", CAIRO_LANG_REPO_PATH, CAIRO_LANG_COMPOSE_FILE);
-        docker::up(shell, &compose_file, false)?;
+        runtime().up(shell, &compose_file, false)?;
     }
 
     Ok(())
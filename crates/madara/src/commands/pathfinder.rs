@@ -3,18 +3,20 @@ use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
-use madara_cli_common::docker;
+use madara_cli_common::runtime::runtime;
 use madara_cli_config::pathfinder::PathfinderRunnerConfigMode;
 use xshell::Shell;
 
 use crate::config::global_config::Config;
 
+use super::deployment::Deployment;
+
 const PATHFINDER_DOCKER_IMAGE: &str = "pathfinder";
 const PATHFINDER_REPO_PATH: &str = "deps/pathfinder";
-const PATHFINDER_RUNNER_SCRIPT: &str = "pathfinder-runner.sh";
+const PATHFINDER_RUNNER_SCRIPT: &str = "pathfinder/pathfinder-runner.sh";
 
 pub fn build_image(shell: &Shell) -> anyhow::Result<()> {
-    docker::build_image(
+    runtime().build_image(
         shell,
         PATHFINDER_REPO_PATH.to_string(),
         PATHFINDER_DOCKER_IMAGE.to_string(),
@@ -39,7 +41,7 @@ fn create_runner_script(params: Vec<String>, output_path: &str) -> anyhow::Resul
 
     // Dump config into pathfinder-runner script
     let path = Path::new(output_path);
-    let mut file = File::create(&path)?;
+    let mut file = File::create(path)?;
     file.write_all(script.as_bytes())?;
 
     // Set execuion permission
@@ -49,7 +51,11 @@ fn create_runner_script(params: Vec<String>, output_path: &str) -> anyhow::Resul
     Ok(())
 }
 
-pub fn parse_params(params: &PathfinderRunnerConfigMode, config: &Config) -> anyhow::Result<()> {
+pub(crate) fn parse_params(
+    params: &PathfinderRunnerConfigMode,
+    config: &Config,
+    deployment: &Deployment,
+) -> anyhow::Result<()> {
     // TODO: handle ethereum_wss.
     let PathfinderRunnerConfigMode {
         network,
@@ -61,7 +67,7 @@ pub fn parse_params(params: &PathfinderRunnerConfigMode, config: &Config) -> any
     } = params;
 
     let pathfinder_params = vec![
-        format!("--network {}", network.to_string()).to_lowercase(),
+        format!("--network {}", network).to_lowercase(),
         format!("--chain-id {}", config.madara.app_chain_id),
         "--ethereum.url http://anvil:8545".to_string(),
         format!("--gateway-url {}", gateway_url),
@@ -71,8 +77,8 @@ pub fn parse_params(params: &PathfinderRunnerConfigMode, config: &Config) -> any
         format!("--http-rpc {}", http_rpc),
     ];
 
-    let runner_script_path = &format!("{}/{}", PATHFINDER_REPO_PATH, PATHFINDER_RUNNER_SCRIPT);
-    create_runner_script(pathfinder_params, runner_script_path)?;
+    let runner_script_path = deployment.path(PATHFINDER_RUNNER_SCRIPT);
+    create_runner_script(pathfinder_params, &runner_script_path)?;
 
     Ok(())
 }
@@ -0,0 +1,277 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context};
+use madara_cli_common::{config::global_config, logger, runtime::runtime, spinner::Spinner};
+use madara_cli_config::compose::Compose;
+use madara_cli_types::stage::Stage;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use xshell::Shell;
+
+use super::{
+    addresses,
+    deployment::Deployment,
+    orchestrator::{MADARA_RPC_PORT, PATHFINDER_RPC_PORT},
+    readiness::{self, ReadinessCheck},
+};
+
+pub(crate) const STATE_FILE: &str = "stages.toml";
+
+/// Content of `stages.toml`, the stages of the deployment that finished
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PipelineState {
+    finished: Vec<Stage>,
+}
+
+impl PipelineState {
+    fn load(path: &str) -> anyhow::Result<Self> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Invalid stage file {}", path))
+    }
+
+    fn save(&self, path: &str) -> anyhow::Result<()> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn is_finished(&self, stage: Stage) -> bool {
+        self.finished.contains(&stage)
+    }
+
+    /// First stage to run, `None` when resuming a deployment whose stages all finished
+    fn first_stage(&self, start: Start) -> anyhow::Result<Option<Stage>> {
+        let first = match start {
+            Start::Beginning => Stage::L1Infra,
+            Start::Resume => match Stage::iter().find(|stage| !self.is_finished(*stage)) {
+                Some(stage) => stage,
+                None => return Ok(None),
+            },
+            Start::From(stage) => {
+                if let Some(missing) = Stage::iter().find(|s| *s < stage && !self.is_finished(*s)) {
+                    bail!(
+                        "Stage `{}` didn't finish yet, it must run before `{}`. Use --resume to continue from the first unfinished stage",
+                        missing,
+                        stage
+                    );
+                }
+                stage
+            }
+        };
+        Ok(Some(first))
+    }
+
+    /// Forget the stages that run again, from `first`
+    fn run_from(&mut self, first: Stage) {
+        self.finished.retain(|stage| *stage < first);
+    }
+}
+
+/// Where the pipeline starts, from the `--resume` and `--from-stage` flags
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Start {
+    /// Every stage, forgetting the ones that finished before
+    Beginning,
+    /// The first stage that didn't finish
+    Resume,
+    /// The given stage, the earlier ones must have finished
+    From(Stage),
+}
+
+impl Start {
+    pub fn new(resume: bool, from_stage: Option<Stage>) -> Self {
+        match (resume, from_stage) {
+            (_, Some(stage)) => Self::From(stage),
+            (true, None) => Self::Resume,
+            (false, None) => Self::Beginning,
+        }
+    }
+}
+
+/// Services started by a stage, in order, with what they must reach before the next one starts
+pub(crate) fn stage_services(stage: Stage) -> &'static [(&'static str, ReadinessCheck)] {
+    match stage {
+        Stage::L1Infra => &[("anvil", ReadinessCheck::Running)],
+        Stage::Verifier => &[("mock_verifier_contract", ReadinessCheck::Completed)],
+        Stage::BootstrapL1 => &[("bootstrapper_l1", ReadinessCheck::Completed)],
+        // The bootstrapper sends transactions to Madara as soon as it starts
+        Stage::Madara => &[
+            ("madara", ReadinessCheck::Rpc(MADARA_RPC_PORT)),
+            ("pathfinder", ReadinessCheck::Rpc(PATHFINDER_RPC_PORT)),
+        ],
+        Stage::BootstrapL2 => &[("bootstrapper_l2", ReadinessCheck::Completed)],
+        Stage::StateOverride => &[("override_state", ReadinessCheck::Completed)],
+        // Compose doesn't wait for the healthcheck of localstack without the dependencies
+        Stage::Orchestrator => &[
+            ("mongodb", ReadinessCheck::Running),
+            ("localstack", ReadinessCheck::Running),
+            ("prover", ReadinessCheck::Running),
+            ("init_mongo", ReadinessCheck::Completed),
+            ("orchestrator", ReadinessCheck::Running),
+        ],
+    }
+}
+
+/// Run the stages of an app-chain deployment one after the other, recording each finished one
+/// in `stages.toml`. When a stage fails, the deployment can be continued from it with
+/// `--resume` instead of deploying the L1 contracts again.
+pub(crate) fn run(shell: &Shell, deployment: &Deployment, start: Start) -> anyhow::Result<()> {
+    let state_file = deployment.path(STATE_FILE);
+    let mut state = PipelineState::load(&state_file)?;
+    let compose_file = deployment.compose_file();
+    let compose = Compose::load(&compose_file)?;
+    let dry_run = global_config().is_dry_run();
+
+    let Some(first) = state.first_stage(start)? else {
+        logger::info("Every stage already finished, starting the services");
+        restart_services(shell, &compose_file, &compose, Stage::iter())?;
+        return Ok(());
+    };
+
+    state.run_from(first);
+    if !state.finished.is_empty() {
+        logger::info(format!(
+            "Continuing from stage `{}`, {} stage(s) already finished",
+            first,
+            state.finished.len()
+        ));
+        restart_services(
+            shell,
+            &compose_file,
+            &compose,
+            state.finished.iter().copied(),
+        )?;
+    }
+    if !dry_run {
+        state.save(&state_file)?;
+    }
+
+    for stage in Stage::iter().filter(|stage| *stage >= first) {
+        let spinner = Spinner::new(&format!("Stage {}...", stage));
+        if let Err(e) = run_stage(shell, deployment, &compose, stage, dry_run) {
+            spinner.fail();
+            return Err(e.context(format!(
+                "Stage `{}` failed, fix the problem and continue from it with `create --resume`",
+                stage
+            )));
+        }
+        spinner.finish();
+
+        if !dry_run {
+            state.finished.push(stage);
+            state.save(&state_file)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_stage(
+    shell: &Shell,
+    deployment: &Deployment,
+    compose: &Compose,
+    stage: Stage,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let compose_file = deployment.compose_file();
+    // Optional services, like the mock prover, are only in some compose files
+    let services = stage_services(stage)
+        .iter()
+        .filter(|(service, _)| compose.services.contains_key(*service));
+
+    if !dry_run {
+        addresses::clear_output(deployment, stage)?;
+    }
+    for check in services {
+        runtime().start_services(shell, &compose_file, &[check.0])?;
+        if !dry_run {
+            readiness::wait_for_services(shell, &compose_file, &[*check])?;
+        }
+    }
+    // The next stages are started with the addresses the bootstrapper reported
+    if !dry_run {
+        addresses::update(deployment, stage)?;
+    }
+    Ok(())
+}
+
+/// Start the long-running services of stages that already finished, in case they were stopped.
+/// The one-shot jobs of these stages are not run again.
+fn restart_services(
+    shell: &Shell,
+    compose_file: &str,
+    compose: &Compose,
+    stages: impl Iterator<Item = Stage>,
+) -> anyhow::Result<()> {
+    let services = stages
+        .flat_map(stage_services)
+        .filter(|(service, check)| {
+            !matches!(check, ReadinessCheck::Completed) && compose.services.contains_key(*service)
+        })
+        .map(|(service, _)| *service)
+        .collect::<Vec<_>>();
+
+    if !services.is_empty() {
+        runtime().start_services(shell, compose_file, &services)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(stages: &[Stage]) -> PipelineState {
+        PipelineState {
+            finished: stages.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_start() {
+        assert!(matches!(Start::new(false, None), Start::Beginning));
+        assert!(matches!(Start::new(true, None), Start::Resume));
+        assert!(matches!(
+            Start::new(false, Some(Stage::Madara)),
+            Start::From(Stage::Madara)
+        ));
+    }
+
+    #[test]
+    fn test_first_stage() {
+        let state = finished(&[Stage::L1Infra, Stage::Verifier, Stage::BootstrapL1]);
+        let first = |start| state.first_stage(start).unwrap();
+
+        assert_eq!(first(Start::Beginning), Some(Stage::L1Infra));
+        assert_eq!(first(Start::Resume), Some(Stage::Madara));
+        assert_eq!(first(Start::From(Stage::Verifier)), Some(Stage::Verifier));
+        assert_eq!(first(Start::From(Stage::Madara)), Some(Stage::Madara));
+        // Bootstrapping L2 needs Madara
+        assert!(state.first_stage(Start::From(Stage::BootstrapL2)).is_err());
+
+        let state = finished(&Stage::iter().collect::<Vec<_>>());
+        assert_eq!(state.first_stage(Start::Resume).unwrap(), None);
+    }
+
+    #[test]
+    fn test_run_from_forgets_later_stages() {
+        let mut state = finished(&[Stage::L1Infra, Stage::Verifier, Stage::BootstrapL1]);
+        state.run_from(Stage::Verifier);
+        assert_eq!(state.finished, [Stage::L1Infra]);
+
+        state.run_from(Stage::L1Infra);
+        assert!(state.finished.is_empty());
+    }
+
+    #[test]
+    fn test_state_file() {
+        let state = finished(&[Stage::L1Infra, Stage::BootstrapL1]);
+        let content = toml::to_string(&state).unwrap();
+        assert_eq!(content, "finished = [\"l1-infra\", \"bootstrap-l1\"]\n");
+
+        let loaded: PipelineState = toml::from_str(&content).unwrap();
+        assert_eq!(loaded.finished, state.finished);
+    }
+}
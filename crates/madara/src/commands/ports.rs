@@ -0,0 +1,225 @@
+use std::{collections::BTreeMap, net::TcpListener};
+
+use anyhow::bail;
+use madara_cli_common::{logger, output};
+use serde::Serialize;
+use xshell::Shell;
+
+use crate::config::{overrides::env_var, ports::PortsConfiguration};
+
+use super::{deployment::Deployment, status};
+
+/// A port published by a service, identified by its key in the `[ports]` configuration section
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PortSpec {
+    pub key: &'static str,
+    pub service: &'static str,
+    pub container_port: u16,
+}
+
+/// A published port with the host port it's bound to
+#[derive(Debug, Clone)]
+pub(crate) struct PortMapping {
+    pub spec: PortSpec,
+    pub host_port: u16,
+}
+
+/// Host ports used by a deployment, from the defaults and the `[ports]` configuration
+pub(crate) struct PortPlan {
+    mappings: Vec<PortMapping>,
+}
+
+impl PortPlan {
+    pub fn new(specs: &[PortSpec], config: &PortsConfiguration) -> Self {
+        let mappings = specs
+            .iter()
+            .map(|spec| PortMapping {
+                spec: *spec,
+                host_port: config.get(spec.key).unwrap_or(spec.container_port),
+            })
+            .collect();
+        Self { mappings }
+    }
+
+    /// Host ports to render into the compose file, by key
+    pub fn host_ports(&self) -> BTreeMap<&'static str, u16> {
+        self.mappings
+            .iter()
+            .map(|mapping| (mapping.spec.key, mapping.host_port))
+            .collect()
+    }
+
+    /// Make sure every host port can be bound before starting anything. Ports already
+    /// published by the deployment itself are fine, as its containers will be recreated.
+    /// With `auto`, busy ports are moved to the next free ones instead of failing.
+    pub fn check(
+        &mut self,
+        shell: &Shell,
+        deployment: &Deployment,
+        auto: bool,
+    ) -> anyhow::Result<()> {
+        let own_ports = deployment
+            .containers(shell)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|container| container.publishers.iter().flatten())
+            .map(|publisher| publisher.published_port)
+            .collect::<Vec<_>>();
+        let is_free = |port: u16| own_ports.contains(&port) || port_is_free(port);
+
+        let mut problems = vec![];
+        for index in 0..self.mappings.len() {
+            let mapping = &self.mappings[index];
+            let duplicate = self.mappings[..index]
+                .iter()
+                .find(|other| other.host_port == mapping.host_port);
+            if let Some(other) = duplicate {
+                problems.push(format!(
+                    "ports.{} and ports.{} both use port {}",
+                    other.spec.key, mapping.spec.key, mapping.host_port
+                ));
+                continue;
+            }
+            if is_free(mapping.host_port) {
+                continue;
+            }
+
+            if !auto {
+                problems.push(format!(
+                    "Port {} of `{}` is already in use, set another one with --set ports.{}=<PORT> or {}",
+                    mapping.host_port,
+                    mapping.spec.service,
+                    mapping.spec.key,
+                    env_var(&format!("ports.{}", mapping.spec.key))
+                ));
+                continue;
+            }
+
+            let taken = self.host_ports().into_values().collect::<Vec<_>>();
+            let Some(port) = (mapping.host_port.saturating_add(1)..=u16::MAX)
+                .find(|port| !taken.contains(port) && is_free(*port))
+            else {
+                bail!("No free port found for `{}`", mapping.spec.service);
+            };
+            logger::info(format!(
+                "Port {} of `{}` is already in use, using {} instead",
+                mapping.host_port, mapping.spec.service, port
+            ));
+            self.mappings[index].host_port = port;
+        }
+
+        if !problems.is_empty() {
+            for problem in &problems {
+                logger::error(problem);
+            }
+            bail!(
+                "{} host port(s) are not available, use --auto-ports to pick free ones",
+                problems.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Mappings whose host port is already bound on this machine
+    pub fn busy(&self) -> Vec<&PortMapping> {
+        self.mappings
+            .iter()
+            .filter(|mapping| !port_is_free(mapping.host_port))
+            .collect()
+    }
+
+    /// Print the host endpoint of every published port
+    pub fn print(&self) -> anyhow::Result<()> {
+        if output::is_json() {
+            let endpoints = self
+                .mappings
+                .iter()
+                .map(|mapping| Endpoint {
+                    service: mapping.spec.service,
+                    key: format!("ports.{}", mapping.spec.key),
+                    host_port: mapping.host_port,
+                    container_port: mapping.spec.container_port,
+                    url: format!("http://localhost:{}", mapping.host_port),
+                })
+                .collect::<Vec<_>>();
+            return output::emit_table("endpoints", endpoints);
+        }
+
+        let headers = ["SERVICE", "PORT", "ENDPOINT"];
+        let rows = self
+            .mappings
+            .iter()
+            .map(|mapping| {
+                [
+                    mapping.spec.service.to_string(),
+                    format!("ports.{}", mapping.spec.key),
+                    format!(
+                        "http://localhost:{} -> {}",
+                        mapping.host_port, mapping.spec.container_port
+                    ),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        logger::note(
+            "Endpoints",
+            status::align_columns(&headers, &rows).join("\n"),
+        );
+        Ok(())
+    }
+}
+
+/// A published port as printed with `--output json`
+#[derive(Debug, Serialize)]
+struct Endpoint {
+    service: &'static str,
+    key: String,
+    host_port: u16,
+    container_port: u16,
+    url: String,
+}
+
+fn port_is_free(port: u16) -> bool {
+    // Docker publishes the ports on every interface
+    TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPECS: [PortSpec; 2] = [
+        PortSpec {
+            key: "madara_rpc",
+            service: "madara",
+            container_port: 9945,
+        },
+        PortSpec {
+            key: "anvil_rpc",
+            service: "anvil",
+            container_port: 8545,
+        },
+    ];
+
+    #[test]
+    fn test_configured_ports_override_defaults() {
+        let config = PortsConfiguration {
+            anvil_rpc: Some(18545),
+            ..Default::default()
+        };
+        let plan = PortPlan::new(&SPECS, &config);
+
+        assert_eq!(plan.host_ports()["madara_rpc"], 9945);
+        assert_eq!(plan.host_ports()["anvil_rpc"], 18545);
+    }
+
+    #[test]
+    fn test_busy_port_is_detected() {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(!port_is_free(port));
+        drop(listener);
+        assert!(port_is_free(port));
+    }
+}
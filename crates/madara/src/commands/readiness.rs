@@ -0,0 +1,329 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use madara_cli_common::{
+    rpc::{decode_short_string, RpcError, StarknetRpcClient, SyncStatus},
+    spinner::Spinner,
+};
+use xshell::Shell;
+
+use super::status::{self, ServiceStatus};
+
+const READINESS_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// A node gets a few block times to produce or sync a block, and never less than the minimum
+const BLOCK_PROGRESS_BLOCKS: u32 = 3;
+const MIN_BLOCK_PROGRESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How to tell that a service is ready, on top of its healthcheck if it has one
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReadinessCheck {
+    /// Long-running service, ready as soon as it's running
+    Running,
+    /// One-shot job, ready once it exited successfully
+    Completed,
+    /// Ready once its RPC server answers on the given container port
+    Rpc(u16),
+}
+
+enum Readiness {
+    Ready,
+    Pending,
+    Failed(String),
+}
+
+/// Poll the services of a compose project started in detached mode until all of them are
+/// ready. Services not listed in `checks` are expected to keep running.
+pub(crate) fn wait_until_ready(
+    shell: &Shell,
+    compose_file: &str,
+    checks: &[(&str, ReadinessCheck)],
+) -> anyhow::Result<Vec<ServiceStatus>> {
+    let spinner = Spinner::new("Waiting for services to be ready...");
+    match poll(shell, compose_file, checks, false) {
+        Ok(statuses) => {
+            spinner.finish();
+            Ok(statuses)
+        }
+        Err(e) => {
+            spinner.fail();
+            Err(e)
+        }
+    }
+}
+
+/// Poll the services listed in `checks` until they are ready, ignoring the other services of
+/// the compose project
+pub(crate) fn wait_for_services(
+    shell: &Shell,
+    compose_file: &str,
+    checks: &[(&str, ReadinessCheck)],
+) -> anyhow::Result<()> {
+    poll(shell, compose_file, checks, true)?;
+    Ok(())
+}
+
+fn poll(
+    shell: &Shell,
+    compose_file: &str,
+    checks: &[(&str, ReadinessCheck)],
+    only_listed: bool,
+) -> anyhow::Result<Vec<ServiceStatus>> {
+    let start = Instant::now();
+
+    loop {
+        let statuses = status::collect(shell, compose_file)?;
+
+        let mut pending = vec![];
+        for service in &statuses {
+            let check = match checks.iter().find(|(name, _)| *name == service.service) {
+                Some((_, check)) => *check,
+                None if only_listed => continue,
+                None => ReadinessCheck::Running,
+            };
+
+            match readiness(service, check) {
+                Readiness::Ready => {}
+                Readiness::Pending => pending.push(service.service.clone()),
+                Readiness::Failed(reason) => {
+                    bail!("Service `{}` failed: {}", service.service, reason);
+                }
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(statuses);
+        }
+
+        if start.elapsed() > READINESS_TIMEOUT {
+            bail!(
+                "Timed out waiting for services to be ready: {}",
+                pending.join(", ")
+            );
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn readiness(service: &ServiceStatus, check: ReadinessCheck) -> Readiness {
+    match (service.state.as_str(), check) {
+        ("exited", ReadinessCheck::Completed) => match service.exit_code {
+            Some(0) => Readiness::Ready,
+            code => Readiness::Failed(format!(
+                "exited with code {}",
+                code.map_or("unknown".to_string(), |c| c.to_string())
+            )),
+        },
+        ("exited", _) | ("dead", _) => Readiness::Failed(format!(
+            "stopped unexpectedly with exit code {}",
+            service
+                .exit_code
+                .map_or("unknown".to_string(), |c| c.to_string())
+        )),
+        ("running", _) if service.health.as_deref() == Some("unhealthy") => {
+            Readiness::Failed("healthcheck reports the service as unhealthy".to_string())
+        }
+        ("running", _) if matches!(service.health.as_deref(), Some(h) if h != "healthy") => {
+            Readiness::Pending
+        }
+        ("running", ReadinessCheck::Running) => Readiness::Ready,
+        ("running", ReadinessCheck::Rpc(port)) => match published_port(service, port) {
+            Some(host_port) if rpc_answers(host_port) => Readiness::Ready,
+            _ => Readiness::Pending,
+        },
+        _ => Readiness::Pending,
+    }
+}
+
+fn published_port(service: &ServiceStatus, container_port: u16) -> Option<u16> {
+    service
+        .published_ports
+        .iter()
+        .find_map(|(published, target)| (*target == container_port).then_some(*published))
+}
+
+fn rpc_client(host_port: u16) -> StarknetRpcClient {
+    StarknetRpcClient::new(&format!("http://localhost:{}", host_port))
+}
+
+// A JSON-RPC error (e.g. no blocks yet) still means the node is answering
+fn rpc_answers(host_port: u16) -> bool {
+    !matches!(
+        rpc_client(host_port).block_number(),
+        Err(RpcError::Transport { .. })
+    )
+}
+
+/// Time a node gets to produce or sync a block, from its block time when it's known
+pub(crate) fn block_progress_timeout(block_time: Option<Duration>) -> Duration {
+    block_time.map_or(MIN_BLOCK_PROGRESS_TIMEOUT, |block_time| {
+        (block_time * BLOCK_PROGRESS_BLOCKS).max(MIN_BLOCK_PROGRESS_TIMEOUT)
+    })
+}
+
+/// Check that a Starknet node started by the CLI reports the expected chain id and, if a
+/// timeout is given, that it's producing or syncing blocks within it
+pub(crate) fn verify_node(
+    statuses: &[ServiceStatus],
+    service: &str,
+    container_port: u16,
+    expected_chain_id: Option<&str>,
+    progress_timeout: Option<Duration>,
+) -> anyhow::Result<()> {
+    let Some(host_port) = statuses
+        .iter()
+        .find(|status| status.service == service)
+        .and_then(|status| published_port(status, container_port))
+    else {
+        bail!(
+            "Service `{}` doesn't publish its RPC port {}",
+            service,
+            container_port
+        );
+    };
+    let client = rpc_client(host_port);
+
+    if let Some(expected_chain_id) = expected_chain_id {
+        let chain_id = client.chain_id()?;
+        let chain_id = decode_short_string(&chain_id).unwrap_or(chain_id);
+        if chain_id != expected_chain_id {
+            bail!(
+                "Service `{}` reports chain id {}, expected {}",
+                service,
+                chain_id,
+                expected_chain_id
+            );
+        }
+    }
+
+    if let Some(timeout) = progress_timeout {
+        wait_for_blocks(&client, service, timeout)?;
+    }
+
+    Ok(())
+}
+
+fn wait_for_blocks(
+    client: &StarknetRpcClient,
+    service: &str,
+    timeout: Duration,
+) -> anyhow::Result<()> {
+    let spinner = Spinner::new(&format!(
+        "Waiting for {} to produce or sync blocks...",
+        service
+    ));
+    let start = Instant::now();
+    let initial_block = client.block_number().ok();
+
+    loop {
+        if let Ok(SyncStatus::Syncing { .. }) = client.syncing() {
+            break;
+        }
+        if let Ok(block) = client.block_number() {
+            match initial_block {
+                Some(initial) if block <= initial => {}
+                _ => break,
+            }
+        }
+
+        if start.elapsed() > timeout {
+            spinner.fail();
+            bail!(
+                "Service `{}` didn't produce or sync any block at {}",
+                service,
+                client.url()
+            );
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    spinner.finish();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(state: &str, health: Option<&str>, exit_code: Option<i32>) -> ServiceStatus {
+        ServiceStatus {
+            service: "service".to_string(),
+            container: Some("service".to_string()),
+            state: state.to_string(),
+            health: health.map(String::from),
+            exit_code,
+            ports: vec![],
+            uptime: None,
+            published_ports: vec![],
+        }
+    }
+
+    #[test]
+    fn test_readiness() {
+        let running = service("running", None, None);
+        assert!(matches!(
+            readiness(&running, ReadinessCheck::Running),
+            Readiness::Ready
+        ));
+        assert!(matches!(
+            readiness(&running, ReadinessCheck::Completed),
+            Readiness::Pending
+        ));
+        // No published port to probe yet
+        assert!(matches!(
+            readiness(&running, ReadinessCheck::Rpc(9945)),
+            Readiness::Pending
+        ));
+
+        let starting = service("running", Some("starting"), None);
+        assert!(matches!(
+            readiness(&starting, ReadinessCheck::Running),
+            Readiness::Pending
+        ));
+        let unhealthy = service("running", Some("unhealthy"), None);
+        assert!(matches!(
+            readiness(&unhealthy, ReadinessCheck::Running),
+            Readiness::Failed(_)
+        ));
+
+        let completed = service("exited", None, Some(0));
+        assert!(matches!(
+            readiness(&completed, ReadinessCheck::Completed),
+            Readiness::Ready
+        ));
+        assert!(matches!(
+            readiness(&completed, ReadinessCheck::Running),
+            Readiness::Failed(_)
+        ));
+        let failed = service("exited", None, Some(1));
+        assert!(matches!(
+            readiness(&failed, ReadinessCheck::Completed),
+            Readiness::Failed(_)
+        ));
+
+        let not_created = service("not created", None, None);
+        assert!(matches!(
+            readiness(&not_created, ReadinessCheck::Completed),
+            Readiness::Pending
+        ));
+    }
+
+    #[test]
+    fn test_block_progress_timeout() {
+        assert_eq!(block_progress_timeout(None), MIN_BLOCK_PROGRESS_TIMEOUT);
+        let six_seconds = Some(Duration::from_secs(6));
+        assert_eq!(
+            block_progress_timeout(six_seconds),
+            MIN_BLOCK_PROGRESS_TIMEOUT
+        );
+        let five_minutes = Some(Duration::from_secs(300));
+        assert_eq!(
+            block_progress_timeout(five_minutes),
+            Duration::from_secs(900)
+        );
+    }
+}
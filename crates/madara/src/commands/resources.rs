@@ -0,0 +1,77 @@
+use std::{fs, thread};
+
+use anyhow::bail;
+use madara_cli_common::logger;
+use xshell::{cmd, Shell};
+
+use crate::config::resources::ResourcesConfiguration;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// Make sure the machine can run the services with their limits. Docker refuses to start a
+/// container with more CPUs than the machine has, while memory limits are only a cap, so
+/// going over the memory of the machine is a warning.
+pub(crate) fn check(
+    shell: &Shell,
+    resources: &ResourcesConfiguration,
+    services: &[&str],
+) -> anyhow::Result<()> {
+    if let Some(cpus) = host_cpus() {
+        for service in services {
+            let limits = resources.limits(service);
+            if limits.cpus > cpus {
+                bail!(
+                    "`{}` is limited to {} CPUs but this machine has {}, lower resources.{}.cpus or use --profile small",
+                    service,
+                    limits.cpus,
+                    cpus,
+                    service
+                );
+            }
+        }
+    }
+
+    if let Some(memory) = host_memory(shell) {
+        let requested = services
+            .iter()
+            .map(|service| resources.limits(service).memory.bytes())
+            .sum::<u64>();
+        if requested > memory {
+            logger::warn(format!(
+                "Services may use up to {} of memory but this machine has {}, lower them in [resources] or use --profile small",
+                format_bytes(requested),
+                format_bytes(memory)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn host_cpus() -> Option<f64> {
+    thread::available_parallelism()
+        .ok()
+        .map(|cpus| cpus.get() as f64)
+}
+
+pub(crate) fn host_memory(shell: &Shell) -> Option<u64> {
+    if let Ok(meminfo) = fs::read_to_string("/proc/meminfo") {
+        let kb = meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix("MemTotal:"))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        return Some(kb * 1024);
+    }
+
+    // macOS
+    let output = cmd!(shell, "sysctl -n hw.memsize").quiet().read().ok()?;
+    output.trim().parse().ok()
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / GB as f64)
+}
@@ -0,0 +1,211 @@
+use anyhow::bail;
+use console::style;
+use madara_cli_common::{docker::ComposeContainer, logger, output, runtime::runtime};
+use madara_cli_config::compose::Compose;
+use serde::Serialize;
+use xshell::Shell;
+
+use super::deployment::Deployment;
+
+const NOT_CREATED_STATE: &str = "not created";
+
+/// State of a single compose service, merged from the compose file and `docker compose ps`
+#[derive(Debug, Serialize)]
+pub(crate) struct ServiceStatus {
+    pub service: String,
+    pub container: Option<String>,
+    pub state: String,
+    pub health: Option<String>,
+    pub exit_code: Option<i32>,
+    pub ports: Vec<String>,
+    pub uptime: Option<String>,
+    /// Published ports as (host port, container port)
+    #[serde(skip)]
+    pub published_ports: Vec<(u16, u16)>,
+}
+
+pub(crate) fn run(name: Option<String>, shell: &Shell) -> anyhow::Result<()> {
+    let deployment = Deployment::resolve(name, shell)?;
+    let compose_file = deployment.compose_file();
+    if !shell.path_exists(&compose_file) {
+        bail!(
+            "Deployment `{}` was never started: {} does not exist",
+            deployment.name(),
+            compose_file
+        );
+    }
+
+    let statuses = collect(shell, &compose_file)?;
+
+    if output::is_json() {
+        return output::emit_table("services", &statuses);
+    }
+
+    logger::note(
+        format!("Services from {}", compose_file),
+        render_table(&statuses),
+    );
+    Ok(())
+}
+
+pub(crate) fn collect(shell: &Shell, compose_file: &str) -> anyhow::Result<Vec<ServiceStatus>> {
+    let compose = Compose::load(compose_file)?;
+    let containers = runtime().ps(shell, compose_file)?;
+
+    let statuses = compose
+        .services
+        .iter()
+        .map(|(name, service)| {
+            let container = containers.iter().find(|c| &c.service == name);
+            // Health is only meaningful for services declaring a healthcheck
+            let has_healthcheck = service.healthcheck.is_some();
+            match container {
+                Some(container) => service_status(name, container, has_healthcheck),
+                None => ServiceStatus {
+                    service: name.clone(),
+                    container: None,
+                    state: NOT_CREATED_STATE.to_string(),
+                    health: None,
+                    exit_code: None,
+                    ports: vec![],
+                    uptime: None,
+                    published_ports: vec![],
+                },
+            }
+        })
+        .collect();
+
+    Ok(statuses)
+}
+
+fn service_status(
+    name: &str,
+    container: &ComposeContainer,
+    has_healthcheck: bool,
+) -> ServiceStatus {
+    let health = match (has_healthcheck, container.health.is_empty()) {
+        (true, false) => Some(container.health.clone()),
+        (true, true) => Some("unknown".to_string()),
+        (false, _) => None,
+    };
+
+    let exit_code = (container.state == "exited").then_some(container.exit_code);
+
+    let publishers = container
+        .publishers
+        .iter()
+        .flatten()
+        .filter(|p| p.published_port != 0)
+        .collect::<Vec<_>>();
+    // IPv4 and IPv6 bindings are reported separately, not always next to each other
+    let ports = unique(
+        publishers
+            .iter()
+            .map(|p| format!("{}->{}/{}", p.published_port, p.target_port, p.protocol)),
+    );
+    let published_ports = unique(publishers.iter().map(|p| (p.published_port, p.target_port)));
+
+    // `Status` looks like "Up 5 minutes (healthy)" for running containers
+    let uptime = container
+        .status
+        .strip_prefix("Up ")
+        .map(|up| up.split(" (").next().unwrap_or(up).to_string());
+
+    ServiceStatus {
+        service: name.to_string(),
+        container: Some(container.name.clone()),
+        state: container.state.clone(),
+        health,
+        exit_code,
+        ports,
+        uptime,
+        published_ports,
+    }
+}
+
+/// Items without their repetitions, in the order they first appear
+fn unique<T: PartialEq>(items: impl Iterator<Item = T>) -> Vec<T> {
+    let mut unique = vec![];
+    for item in items {
+        if !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+    unique
+}
+
+fn render_table(statuses: &[ServiceStatus]) -> String {
+    let headers = ["SERVICE", "STATE", "HEALTH", "EXIT", "PORTS", "UPTIME"];
+    let rows: Vec<[String; 6]> = statuses
+        .iter()
+        .map(|s| {
+            [
+                s.service.clone(),
+                s.state.clone(),
+                s.health.clone().unwrap_or("-".to_string()),
+                s.exit_code.map_or("-".to_string(), |c| c.to_string()),
+                if s.ports.is_empty() {
+                    "-".to_string()
+                } else {
+                    s.ports.join(", ")
+                },
+                s.uptime.clone().unwrap_or("-".to_string()),
+            ]
+        })
+        .collect();
+
+    let lines = align_columns(&headers, &rows);
+    let mut table = style(&lines[0]).bold().to_string();
+    for (line, status) in lines[1..].iter().zip(statuses) {
+        let line = match (status.state.as_str(), status.exit_code) {
+            ("running", _) if status.health.as_deref() == Some("unhealthy") => style(line).yellow(),
+            ("running", _) | ("exited", Some(0)) => style(line),
+            (NOT_CREATED_STATE, _) => style(line).dim(),
+            _ => style(line).red(),
+        };
+        table.push('\n');
+        table.push_str(&line.to_string());
+    }
+
+    table
+}
+
+/// Pad the cells of a table so its columns are aligned, the first line being the headers
+pub(crate) fn align_columns<const N: usize>(
+    headers: &[&str; N],
+    rows: &[[String; N]],
+) -> Vec<String> {
+    let mut widths = headers.map(|h| h.len());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    std::iter::once(format_row(&headers.map(String::from)))
+        .chain(rows.iter().map(|row| format_row(row)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique() {
+        let ports = ["9944->9944/tcp", "8080->8080/tcp", "9944->9944/tcp"];
+        assert_eq!(
+            unique(ports.into_iter()),
+            ["9944->9944/tcp", "8080->8080/tcp"]
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs;
+
+use madara_cli_common::{config::global_config, logger, runtime::runtime, spinner::Spinner};
+use xshell::Shell;
+
+use super::deployment::Deployment;
+
+pub(crate) fn run(name: Option<String>, wipe: bool, shell: &Shell) -> anyhow::Result<()> {
+    let deployment = Deployment::select(name)?;
+    let name = deployment.name().to_string();
+
+    let compose_file = deployment.compose_file();
+    let data_dirs = deployment.data_dirs(shell)?;
+
+    // A deployment that failed before rendering its compose file has nothing to stop
+    if shell.path_exists(&compose_file) {
+        let spinner = Spinner::new("Stopping services...");
+        runtime().down(shell, &compose_file, wipe)?;
+        spinner.finish();
+    }
+
+    if wipe && global_config().is_dry_run() {
+        for data_dir in data_dirs.iter().filter(|dir| dir.exists()) {
+            logger::step(format!(
+                "Dry run, skipping the removal of: {}",
+                data_dir.display()
+            ));
+        }
+        logger::step(format!(
+            "Dry run, skipping the removal of: {}",
+            deployment.dir()
+        ));
+    } else if wipe {
+        for data_dir in data_dirs.iter().filter(|dir| dir.exists()) {
+            logger::info(format!("Removing data directory: {}", data_dir.display()));
+            fs::remove_dir_all(data_dir)?;
+        }
+        logger::info(format!(
+            "Removing deployment directory: {}",
+            deployment.dir()
+        ));
+        deployment.remove()?;
+    } else if !data_dirs.is_empty() {
+        logger::info("Data directories were kept, use --wipe to remove them");
+    }
+
+    logger::outro(format!("Deployment `{}` stopped", name));
+    Ok(())
+}
@@ -1,19 +1,37 @@
-use figment::{
-    providers::{Format, Json},
-    Figment,
+use madara_cli_common::{
+    logger,
+    validation::{validate_felt, validate_starknet_address, validate_u64, validate_url},
+    Prompt, PromptConfirm, PromptSelect,
 };
-
 use serde::{Deserialize, Serialize};
-use std::fs;
+use strum::IntoEnumIterator;
+
+use super::{
+    error::ConfigResult,
+    global_config::Config,
+    io::{self, ConfigFormat},
+};
+
+const ROLLUP_SEQ_URL: &str = "http://madara:9945";
+const ROLLUP_DECLARE_V0_SEQ_URL: &str = "http://madara:9943";
+const ROLLUP_PRIV_KEY: &str = "0xabcd";
+const L1_WAIT_TIME: u64 = 15;
+const SN_OS_PROGRAM_HASH: &str =
+    "0x41fc2a467ef8649580631912517edcab7674173f1dbfa2e9b64fbcd82bc4d79";
+const CONFIG_HASH_VERSION: &str = "StarknetOsConfig2";
+const CROSS_CHAIN_WAIT_TIME: u32 = 20;
+const L2_MULTISIG_ADDRESS: &str =
+    "0x556455b8ac8bc00e0ad061d7df5458fa3c372304877663fa21d492a8d5e9435";
 
+/// Content of the configuration file read by `madara-bootstrapper`
 #[derive(Serialize, Deserialize, Debug)]
-pub struct BootstrapperConfiguration {
+pub struct BootstrapperFileConfiguration {
     pub eth_rpc: String,
     pub eth_priv_key: String,
     pub rollup_seq_url: String,
     pub rollup_declare_v0_seq_url: String,
     pub rollup_priv_key: String,
-    pub eth_chain_id: u32,
+    pub eth_chain_id: u64,
     pub l1_deployer_address: String,
     pub l1_wait_time: String,
     pub sn_os_program_hash: String,
@@ -29,21 +47,142 @@ pub struct BootstrapperConfiguration {
     pub dev: bool,
     pub core_contract_address: String,
     pub core_contract_implementation_address: String,
-    pub core_contract_mode: String,
+    pub core_contract_mode: CoreContractMode,
     pub l1_eth_bridge_address: String,
 }
 
-impl BootstrapperConfiguration {
-    pub fn load(file_path: &str) -> BootstrapperConfiguration {
-        Figment::new()
-            .merge(Json::file(file_path))
-            .extract::<BootstrapperConfiguration>()
-            .expect("Failed to load configuration")
+impl BootstrapperFileConfiguration {
+    pub fn load(file_path: &str) -> ConfigResult<BootstrapperFileConfiguration> {
+        io::load(file_path, ConfigFormat::Json)
+    }
+
+    pub fn save(&self, file_path: &str) -> ConfigResult<()> {
+        io::save(file_path, serde_json::to_string_pretty(self))
+    }
+}
+
+/// Core contract deployed on L1 by the bootstrapper
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display, strum::EnumIter,
+)]
+pub enum CoreContractMode {
+    Production,
+    Dev,
+}
+
+/// Settings of the bootstrapper that are not taken from the other sections: the L1 values,
+/// wallet addresses, chain id and fee tokens are filled from the L1, wallet and Madara
+/// sections when the configuration file of the bootstrapper is rendered
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BootstrapperConfiguration {
+    /// Madara RPC the L2 contracts are deployed through, as seen from the bootstrapper container
+    pub rollup_seq_url: String,
+    /// Madara RPC accepting the declaration of Cairo 0 classes
+    pub rollup_declare_v0_seq_url: String,
+    /// Private key of the L2 account deploying the L2 contracts
+    pub rollup_priv_key: String,
+    /// Seconds to wait for the L1 transactions
+    pub l1_wait_time: u64,
+    /// Program hash of the Starknet OS registered in the core contract
+    pub sn_os_program_hash: String,
+    pub config_hash_version: String,
+    pub core_contract_mode: CoreContractMode,
+    /// Seconds to wait for the L1 to L2 messages
+    pub cross_chain_wait_time: u32,
+    pub l2_multisig_address: String,
+    pub dev: bool,
+}
+
+impl Default for BootstrapperConfiguration {
+    fn default() -> Self {
+        Self {
+            rollup_seq_url: ROLLUP_SEQ_URL.to_string(),
+            rollup_declare_v0_seq_url: ROLLUP_DECLARE_V0_SEQ_URL.to_string(),
+            rollup_priv_key: ROLLUP_PRIV_KEY.to_string(),
+            l1_wait_time: L1_WAIT_TIME,
+            sn_os_program_hash: SN_OS_PROGRAM_HASH.to_string(),
+            config_hash_version: CONFIG_HASH_VERSION.to_string(),
+            core_contract_mode: CoreContractMode::Dev,
+            cross_chain_wait_time: CROSS_CHAIN_WAIT_TIME,
+            l2_multisig_address: L2_MULTISIG_ADDRESS.to_string(),
+            dev: false,
+        }
     }
+}
+
+impl BootstrapperConfiguration {
+    pub fn init(template: &mut Config, default: bool) -> anyhow::Result<()> {
+        logger::new_empty_line();
+        logger::note(
+            "Bootstrapper configuration",
+            "You'll need to setup the parameters used to deploy the L1 and L2 contracts",
+        );
+        let rollup_seq_url = Prompt::new("Enter the Madara RPC URL (e.g., http://madara:9945)")
+            .default(&template.bootstrapper.rollup_seq_url)
+            .validate_interactively(validate_url)
+            .default_or_ask(default);
+        let rollup_declare_v0_seq_url = Prompt::new(
+            "Enter the Madara RPC URL to declare Cairo 0 classes (e.g., http://madara:9943)",
+        )
+        .default(&template.bootstrapper.rollup_declare_v0_seq_url)
+        .validate_interactively(validate_url)
+        .default_or_ask(default);
+        let rollup_priv_key = Prompt::new("Enter the private key of the L2 deployer (e.g., 0x...)")
+            .default(&template.bootstrapper.rollup_priv_key)
+            .validate_interactively(validate_felt)
+            .default_or_ask(default);
+        let l2_multisig_address = Prompt::new("Enter the L2 multisig address (e.g., 0x...)")
+            .default(&template.bootstrapper.l2_multisig_address)
+            .validate_interactively(validate_starknet_address)
+            .default_or_ask(default);
+        let sn_os_program_hash = Prompt::new("Enter the Starknet OS program hash (e.g., 0x...)")
+            .default(&template.bootstrapper.sn_os_program_hash)
+            .validate_interactively(validate_felt)
+            .default_or_ask(default);
+        let config_hash_version =
+            Prompt::new("Enter the config hash version (e.g., StarknetOsConfig2)")
+                .default(&template.bootstrapper.config_hash_version)
+                .default_or_ask(default);
+        let l1_wait_time = Prompt::new("Enter the time to wait for L1 transactions (in seconds)")
+            .default(&template.bootstrapper.l1_wait_time.to_string())
+            .validate_interactively(validate_u64)
+            .default_or_ask::<u64>(default);
+        let cross_chain_wait_time =
+            Prompt::new("Enter the time to wait for L1 to L2 messages (in seconds)")
+                .default(&template.bootstrapper.cross_chain_wait_time.to_string())
+                .validate_interactively(validate_u64)
+                .default_or_ask::<u32>(default);
+
+        let (core_contract_mode, dev) = match default {
+            true => (
+                template.bootstrapper.core_contract_mode,
+                template.bootstrapper.dev,
+            ),
+            false => {
+                // The current mode is listed first, as the default choice
+                let current = template.bootstrapper.core_contract_mode;
+                let modes = std::iter::once(current)
+                    .chain(CoreContractMode::iter().filter(|mode| *mode != current));
+                let mode = PromptSelect::new("Select the core contract mode", modes).ask();
+                let dev = PromptConfirm::new("Run the bootstrapper in dev mode?")
+                    .default(template.bootstrapper.dev)
+                    .ask();
+                (mode, dev)
+            }
+        };
 
-    pub fn save(&self, file_path: &str) {
-        let json = serde_json::to_string_pretty(self)
-            .expect("Failed to serialize bootstrapper configuration");
-        fs::write(file_path, json).expect("Failed to write configuration");
+        template.bootstrapper = BootstrapperConfiguration {
+            rollup_seq_url,
+            rollup_declare_v0_seq_url,
+            rollup_priv_key,
+            l1_wait_time,
+            sn_os_program_hash,
+            config_hash_version,
+            core_contract_mode,
+            cross_chain_wait_time,
+            l2_multisig_address,
+            dev,
+        };
+        Ok(())
     }
 }
@@ -0,0 +1,17 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Changes merged into the rendered compose file of every deployment
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ComposeConfiguration {
+    /// Service definitions by service name, merged like a `--compose-override` file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, toml::Table>,
+}
+
+impl ComposeConfiguration {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
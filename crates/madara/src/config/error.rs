@@ -0,0 +1,73 @@
+use std::{fmt, io};
+
+use super::validation::ConfigProblem;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    /// The configuration file doesn't exist
+    #[error("Configuration file {path} not found")]
+    NotFound { path: String },
+    /// A configuration source (file, environment variable or `--set` flag) can't be parsed
+    /// or doesn't match the expected structure
+    #[error("Invalid configuration in {}: {message}", describe(.origin, .key, .position))]
+    Invalid {
+        origin: String,
+        key: Option<String>,
+        position: Option<Position>,
+        message: String,
+    },
+    /// The configuration is well-formed but some values are invalid
+    #[error("Invalid configuration:\n{}", list_problems(.problems))]
+    Validation { problems: Vec<ConfigProblem> },
+    #[error("Invalid --set flag `{flag}`, expected KEY=VALUE (e.g. madara.block_time=6s)")]
+    InvalidOverride { flag: String },
+    /// The configuration can't be serialized to the file format
+    #[error("Failed to serialize configuration for {path}: {reason}")]
+    Serialize { path: String, reason: String },
+    #[error("Failed to read configuration from {path}: {source}")]
+    Read {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Failed to write configuration to {path}: {source}")]
+    Write {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// Line and column (1-based) of a syntax error in a configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+fn list_problems(problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("  {}", problem))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn describe(origin: &str, key: &Option<String>, position: &Option<Position>) -> String {
+    let mut description = origin.to_string();
+    if let Some(position) = position {
+        description.push_str(&format!(" ({})", position));
+    }
+    if let Some(key) = key {
+        description.push_str(&format!(" at key `{}`", key));
+    }
+    description
+}
@@ -81,13 +81,19 @@ impl EthWallet {
         }
     }
 
-    pub fn get_address(priv_key: &String) -> (PublicKey, String) {
+    pub fn get_address(priv_key: &str) -> (PublicKey, String) {
+        Self::try_get_address(priv_key).expect("Invalid private key")
+    }
+
+    pub fn try_get_address(priv_key: &str) -> Result<(PublicKey, String), String> {
         let secp = Secp256k1::signing_only();
         let priv_key = priv_key.trim_start_matches("0x");
 
         // Convert the private key from a hex string to a SecretKey object
-        let priv_key_bytes = hex::decode(priv_key).expect("Invalid hex string");
-        let secret_key = SecretKey::from_slice(&priv_key_bytes).expect("Invalid private key");
+        let priv_key_bytes =
+            hex::decode(priv_key).map_err(|e| format!("Invalid hex string: {}", e))?;
+        let secret_key = SecretKey::from_slice(&priv_key_bytes)
+            .map_err(|e| format!("Invalid private key: {}", e))?;
 
         // Generate the public key from the private key
         let public_key = PublicKey::from_secret_key(&secp, &secret_key);
@@ -102,7 +108,7 @@ impl EthWallet {
         // Take the last 20 bytes of the hash to form the Ethereum address
         let address = format!("0x{}", hex::encode(&hash[12..]));
 
-        (public_key, address)
+        Ok((public_key, address))
     }
 
     pub fn init(template: &mut Config, default: bool) -> anyhow::Result<()> {
@@ -111,21 +117,35 @@ impl EthWallet {
             "L1 wallet configuration",
             "You'll need to setup all the parameters related to your L1 or settlement layer wallet",
         );
-        let eth_priv_key = Prompt::new("Enter the L1 private key (e.g., 0x...)")
+        let eth_priv_key: String = Prompt::new("Enter the L1 private key (e.g., 0x...)")
             .default(&template.eth_wallet.eth_priv_key)
-            .validate_interactively(validate_private_key)
-            .default_or_ask(default);
-        let l1_deployer_address = Prompt::new("Enter the L1 deployer address (e.g., 0x...)")
-            .default(&&template.eth_wallet.l1_deployer_address)
-            .validate_interactively(validate_eth_address)
-            .default_or_ask(default);
-        let l1_operator_address = Prompt::new("Enter the L1 operator address (e.g., 0x...)")
-            .default(&template.eth_wallet.l1_operator_address)
-            .validate_interactively(validate_eth_address)
+            .validate_interactively(|val: &str| {
+                validate_private_key(val)?;
+                Self::try_get_address(val).map(|_| ())
+            })
             .default_or_ask(default);
+
+        // Deployer and operator are the account of the private key
+        let (_, l1_deployer_address) = Self::try_get_address(&eth_priv_key)
+            .map_err(|e| anyhow::anyhow!("Invalid L1 private key: {}", e))?;
+        let l1_operator_address = l1_deployer_address.clone();
+        logger::info(format!(
+            "L1 deployer and operator address: {}",
+            l1_deployer_address
+        ));
+
+        let deployer_address = l1_deployer_address.clone();
         let l1_multisig_address = Prompt::new("Enter the L1 multisig address (e.g., 0x...)")
             .default(&template.eth_wallet.l1_multisig_address)
-            .validate_interactively(validate_eth_address)
+            .validate_interactively(move |val: &str| {
+                validate_eth_address(val)?;
+                if val.eq_ignore_ascii_case(&deployer_address) {
+                    return Err(
+                        "Multisig address must be different from the deployer address".to_string(),
+                    );
+                }
+                Ok(())
+            })
             .default_or_ask(default);
 
         template.eth_wallet.eth_priv_key = eth_priv_key;
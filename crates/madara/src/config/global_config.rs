@@ -1,18 +1,27 @@
-use std::fs;
-
+use clap::ValueEnum;
 use figment::{
-    providers::{Format, Toml},
+    providers::{Format, Serialized, Toml},
     Figment,
 };
-use madara_cli_common::{logger, validation::validate_filename, Prompt};
+use madara_cli_common::{logger, validation::validate_filename, Prompt, PromptSelect};
 
 use serde::{Deserialize, Serialize};
-
-use crate::constants::DEFAULT_LOCAL_CONFIG_FILE;
+use std::{fmt, path::Path};
+use strum::{EnumIter, IntoEnumIterator};
 
 use super::{
-    eth_wallet::EthWallet, l1_config::L1Configuration, madara::MadaraConfiguration,
+    bootstrapper::BootstrapperConfiguration,
+    compose::ComposeConfiguration,
+    error::ConfigResult,
+    eth_wallet::EthWallet,
+    io::{self, ConfigFormat},
+    l1_config::L1Configuration,
+    madara::MadaraConfiguration,
     orchestrator::OrchestratorConfiguration,
+    overrides::{self, EnvOverrides, SetOverride},
+    ports::PortsConfiguration,
+    resources::ResourcesConfiguration,
+    LOCAL_CONFIG_NAME, LOCAL_CONFIG_TEMPLATE,
 };
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
@@ -21,47 +30,175 @@ pub struct Config {
     pub eth_wallet: EthWallet,
     pub madara: MadaraConfiguration,
     pub orchestrator: OrchestratorConfiguration,
+    #[serde(default)]
+    pub bootstrapper: BootstrapperConfiguration,
+    #[serde(default, skip_serializing_if = "PortsConfiguration::is_default")]
+    pub ports: PortsConfiguration,
+    #[serde(default, skip_serializing_if = "ResourcesConfiguration::is_default")]
+    pub resources: ResourcesConfiguration,
+    #[serde(default, skip_serializing_if = "ComposeConfiguration::is_default")]
+    pub compose: ComposeConfiguration,
 }
 
 impl Config {
-    pub fn load(config_file: &str) -> Config {
-        Figment::new()
-            .merge(Toml::file(config_file))
-            .extract::<Config>()
-            .expect("Failed to load configuration")
+    pub fn load(config_file: &str) -> ConfigResult<Config> {
+        io::load(config_file, ConfigFormat::Toml)
+    }
+
+    pub fn save(&self, file_path: &str) -> ConfigResult<()> {
+        io::save(file_path, toml::to_string(self))
+    }
+
+    /// The built-in `local.toml` template
+    pub fn local_template() -> ConfigResult<Config> {
+        io::extract(
+            &Figment::from(Toml::string(LOCAL_CONFIG_TEMPLATE)),
+            LOCAL_CONFIG_NAME,
+        )
+    }
+
+    /// Save into an existing file, keeping the user's comments, formatting and the keys
+    /// the CLI doesn't know about
+    pub fn save_in_place(&self, file_path: &str) -> ConfigResult<()> {
+        let previous = Config::load(file_path)?;
+        io::save_toml_in_place(file_path, &previous, self)
+    }
+
+    /// Layer every configuration source, from lowest to highest precedence: built-in
+    /// defaults, `local.toml`, the user file, `MADARA_CLI__SECTION__KEY` environment
+    /// variables and `--set section.key=value` flags
+    pub fn figment(config_file: Option<&str>, overrides: &[String]) -> ConfigResult<Figment> {
+        Self::layers(config_file, overrides::process_env(), overrides)
+    }
+
+    /// [`Config::figment`] with the given environment variables
+    pub(crate) fn layers(
+        config_file: Option<&str>,
+        env: impl IntoIterator<Item = (String, String)>,
+        overrides: &[String],
+    ) -> ConfigResult<Figment> {
+        let mut figment = Figment::new()
+            .merge(Serialized::defaults(Config::default()))
+            .merge(Toml::string(LOCAL_CONFIG_TEMPLATE));
+
+        if let Some(config_file) = config_file {
+            io::ensure_exists(config_file)?;
+            figment = figment.merge(Toml::file(config_file));
+        }
+
+        // Overrides take the type of the values they replace
+        let base = figment.clone();
+        figment = figment.merge(EnvOverrides::new(env, &base));
+        for flag in overrides {
+            figment = figment.merge(SetOverride::parse(flag, &base)?);
+        }
+
+        Ok(figment)
     }
 
-    pub fn save(&self, file_path: &str) {
-        let toml = toml::to_string(self).expect("Failed to serialize global config to TOML");
-        fs::write(file_path, toml).expect("Failed to write configuration");
+    /// Load the configuration from all the sources, see [`Config::figment`]
+    pub fn resolve(config_file: Option<&str>, overrides: &[String]) -> ConfigResult<Config> {
+        let figment = Self::figment(config_file, overrides)?;
+        io::extract(&figment, config_file.unwrap_or(LOCAL_CONFIG_NAME))
     }
 
-    pub fn init(default: bool) -> anyhow::Result<()> {
+    /// Create a new configuration file in `deps/data`, prompting for every section. The
+    /// prompt defaults come from `from` if provided, otherwise from `local.toml`.
+    pub fn init(default: bool, from: Option<&str>) -> anyhow::Result<()> {
         logger::new_empty_line();
         logger::intro("CLI Configuration File Initialization");
 
+        let default_file_name = from
+            .and_then(|from| Path::new(from).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "my_custom_config.toml".to_string());
         let config_file_name: String =
             Prompt::new("Please enter the name for your configuration file")
-                .default("my_custom_config.toml")
+                .default(&default_file_name)
                 .validate_interactively(validate_filename)
                 .default_or_ask(default);
-        let mut local_template = Config::load(DEFAULT_LOCAL_CONFIG_FILE);
+        let mut local_template = match from {
+            Some(from) => Config::load(from)?,
+            None => Config::local_template()?,
+        };
 
-        println!("CONFIG FILE NAME: {}", config_file_name);
+        for section in ConfigSection::iter() {
+            section.init(&mut local_template, default)?;
+        }
 
-        // L1 configuration
-        L1Configuration::init(&mut local_template, default)?;
+        // Let the user go back to any section before saving
+        if !default {
+            loop {
+                local_template.review();
+                match PromptSelect::new("Is this configuration correct?", ReviewAction::all()).ask()
+                {
+                    ReviewAction::Save => break,
+                    ReviewAction::Edit(section) => section.init(&mut local_template, false)?,
+                }
+            }
+        }
 
-        // ETH Wallet configuration
-        EthWallet::init(&mut local_template, default)?;
+        let file_path = format!("deps/data/{}", config_file_name);
+        local_template.save(&file_path)?;
+        logger::outro(format!("Configuration saved to {}", file_path));
+        Ok(())
+    }
 
-        // Madara configuration
-        MadaraConfiguration::init(&mut local_template, default)?;
+    fn review(&self) {
+        logger::new_empty_line();
+        logger::note("Configuration review", logger::object_to_string(self));
+        for problem in self.problems() {
+            logger::warn(problem);
+        }
+    }
+}
 
-        // Orchestrator configuration
-        OrchestratorConfiguration::init(&mut local_template, default)?;
+/// Sections of the configuration, each one filled by its own prompts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, EnumIter, strum::Display)]
+pub enum ConfigSection {
+    #[strum(to_string = "L1 configuration")]
+    L1,
+    #[strum(to_string = "ETH wallet")]
+    Wallet,
+    #[strum(to_string = "Madara configuration")]
+    Madara,
+    #[strum(to_string = "Orchestrator configuration")]
+    Orchestrator,
+    #[strum(to_string = "Bootstrapper configuration")]
+    Bootstrapper,
+}
 
-        local_template.save(&format!("deps/data/{}", config_file_name));
-        Ok(())
+impl ConfigSection {
+    pub fn init(self, config: &mut Config, default: bool) -> anyhow::Result<()> {
+        match self {
+            ConfigSection::L1 => L1Configuration::init(config, default),
+            ConfigSection::Wallet => EthWallet::init(config, default),
+            ConfigSection::Madara => MadaraConfiguration::init(config, default),
+            ConfigSection::Orchestrator => OrchestratorConfiguration::init(config, default),
+            ConfigSection::Bootstrapper => BootstrapperConfiguration::init(config, default),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReviewAction {
+    Save,
+    Edit(ConfigSection),
+}
+
+impl ReviewAction {
+    fn all() -> Vec<ReviewAction> {
+        std::iter::once(ReviewAction::Save)
+            .chain(ConfigSection::iter().map(ReviewAction::Edit))
+            .collect()
+    }
+}
+
+impl fmt::Display for ReviewAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewAction::Save => write!(f, "Yes, save it"),
+            ReviewAction::Edit(section) => write!(f, "No, edit the {}", section),
+        }
     }
 }
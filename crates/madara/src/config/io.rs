@@ -0,0 +1,363 @@
+use std::{fmt, fs, path::Path};
+
+use figment::{
+    error::Kind,
+    providers::{Format, Json, Toml, Yaml},
+    Figment,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use super::error::{ConfigError, ConfigResult, Position};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "json" => Some(ConfigFormat::Json),
+            _ => None,
+        }
+    }
+
+    fn figment(&self, file_path: &str) -> Figment {
+        match self {
+            ConfigFormat::Toml => Figment::new().merge(Toml::file(file_path)),
+            ConfigFormat::Yaml => Figment::new().merge(Yaml::file(file_path)),
+            ConfigFormat::Json => Figment::new().merge(Json::file(file_path)),
+        }
+    }
+
+    /// Figment only keeps the message of syntax errors, parse the content again to
+    /// recover where the error is
+    fn syntax_error(&self, content: &str) -> Option<(String, Option<Position>)> {
+        match self {
+            ConfigFormat::Toml => {
+                let e = toml::from_str::<toml::Value>(content).err()?;
+                let position = e
+                    .span()
+                    .map(|span| position_from_offset(content, span.start));
+                Some((e.message().to_string(), position))
+            }
+            ConfigFormat::Yaml => {
+                let e = serde_yaml::from_str::<serde_yaml::Value>(content).err()?;
+                let position = e.location().map(|location| Position {
+                    line: location.line(),
+                    column: location.column(),
+                });
+                Some((strip_position(e.to_string(), position), position))
+            }
+            ConfigFormat::Json => {
+                let e = serde_json::from_str::<serde_json::Value>(content).err()?;
+                let position = Some(Position {
+                    line: e.line(),
+                    column: e.column(),
+                });
+                Some((strip_position(e.to_string(), position), position))
+            }
+        }
+    }
+}
+
+fn position_from_offset(content: &str, offset: usize) -> Position {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    Position { line, column }
+}
+
+// serde_yaml and serde_json append the position to their message
+fn strip_position(message: String, position: Option<Position>) -> String {
+    match position {
+        Some(position) => {
+            let suffix = format!(" at line {} column {}", position.line, position.column);
+            message
+                .strip_suffix(&suffix)
+                .map(str::to_string)
+                .unwrap_or(message)
+        }
+        None => message,
+    }
+}
+
+/// Load a configuration file, turning missing files, syntax errors and invalid values
+/// into a [`ConfigError`]
+pub(crate) fn load<T: DeserializeOwned>(file_path: &str, format: ConfigFormat) -> ConfigResult<T> {
+    ensure_exists(file_path)?;
+    extract(&format.figment(file_path), file_path)
+}
+
+pub(crate) fn ensure_exists(file_path: &str) -> ConfigResult<()> {
+    if !Path::new(file_path).is_file() {
+        return Err(ConfigError::NotFound {
+            path: file_path.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Extract a configuration from layered sources, `fallback_origin` is reported when
+/// figment doesn't know which source an error comes from
+pub(crate) fn extract<T: DeserializeOwned>(
+    figment: &Figment,
+    fallback_origin: &str,
+) -> ConfigResult<T> {
+    figment.extract::<T>().map_err(|e| {
+        // Only report the first error, the others usually follow from it
+        let e = e
+            .into_iter()
+            .next()
+            .expect("figment errors are never empty");
+        invalid(e, fallback_origin)
+    })
+}
+
+fn invalid(e: figment::Error, fallback_origin: &str) -> ConfigError {
+    let file = e
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.source.as_ref())
+        .and_then(|source| source.file_path());
+    let origin = match (file, &e.metadata) {
+        (Some(file), _) => file.display().to_string(),
+        (None, Some(metadata)) => metadata.name.to_string(),
+        (None, None) => fallback_origin.to_string(),
+    };
+
+    if let (Kind::Message(_), Some(file)) = (&e.kind, file) {
+        let syntax_error = ConfigFormat::from_path(file).and_then(|format| {
+            let content = fs::read_to_string(file).ok()?;
+            format.syntax_error(&content)
+        });
+        if let Some((message, position)) = syntax_error {
+            return ConfigError::Invalid {
+                origin,
+                key: None,
+                position,
+                message,
+            };
+        }
+    }
+
+    let mut key = e.path.clone();
+    if let Kind::MissingField(field) = &e.kind {
+        key.push(field.to_string());
+    }
+
+    ConfigError::Invalid {
+        origin,
+        key: (!key.is_empty()).then(|| key.join(".")),
+        position: None,
+        message: e.kind.to_string(),
+    }
+}
+
+/// Write serialized configuration to a file
+pub(crate) fn save(
+    file_path: &str,
+    serialized: Result<String, impl fmt::Display>,
+) -> ConfigResult<()> {
+    let content = serialized.map_err(|e| ConfigError::Serialize {
+        path: file_path.to_string(),
+        reason: e.to_string(),
+    })?;
+    fs::write(file_path, content).map_err(|source| ConfigError::Write {
+        path: file_path.to_string(),
+        source,
+    })
+}
+
+/// Update an existing TOML file from the `previous` values read from it to new values, keeping
+/// its comments, formatting and key order. Keys that were set in `previous` but no longer are
+/// removed, the ones `T` doesn't know about are kept as they are.
+pub(crate) fn save_toml_in_place<T: Serialize>(
+    file_path: &str,
+    previous: &T,
+    value: &T,
+) -> ConfigResult<()> {
+    ensure_exists(file_path)?;
+    let content = fs::read_to_string(file_path).map_err(|source| ConfigError::Read {
+        path: file_path.to_string(),
+        source,
+    })?;
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|e| ConfigError::Invalid {
+            origin: file_path.to_string(),
+            key: None,
+            position: e
+                .span()
+                .map(|span| position_from_offset(&content, span.start)),
+            message: e.message().to_string(),
+        })?;
+
+    let serialize = |value: &T| {
+        toml_edit::ser::to_document(value).map_err(|e| ConfigError::Serialize {
+            path: file_path.to_string(),
+            reason: e.to_string(),
+        })
+    };
+    let previous = serialize(previous)?;
+    let updated = serialize(value)?;
+    merge_table(
+        document.as_table_mut(),
+        Some(previous.as_table()),
+        updated.as_table(),
+    );
+
+    save(file_path, Ok::<_, String>(document.to_string()))
+}
+
+fn merge_table(existing: &mut Table, previous: Option<&Table>, updated: &Table) {
+    let removed = existing
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| {
+            !updated.contains_key(key)
+                && previous.is_some_and(|previous| previous.contains_key(key))
+        })
+        .collect::<Vec<_>>();
+    for key in removed {
+        existing.remove(&key);
+    }
+
+    for (key, item) in updated.iter() {
+        match (existing.get_mut(key), as_table(item), item) {
+            (Some(Item::Table(existing)), Some(updated), _) => {
+                let previous = previous
+                    .and_then(|previous| previous.get(key))
+                    .and_then(as_table);
+                merge_table(existing, previous.as_ref(), &updated)
+            }
+            (Some(Item::Value(existing)), None, Item::Value(updated)) => {
+                let decor = existing.decor().clone();
+                *existing = updated.clone();
+                *existing.decor_mut() = decor;
+            }
+            (_, Some(updated), _) => {
+                existing.insert(key, Item::Table(updated));
+            }
+            _ => {
+                existing.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+// Serialized structs are inline tables, sections of the file are standard tables
+fn as_table(item: &Item) -> Option<Table> {
+    match item {
+        Item::Table(table) => Some(table.clone()),
+        Item::Value(Value::InlineTable(table)) => Some(table.clone().into_table()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Sample {
+        section: Section,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Section {
+        name: String,
+        port: u16,
+    }
+
+    fn write_sample(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("madara-cli-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_save_toml_in_place_keeps_comments() {
+        #[derive(Serialize)]
+        struct Updated {
+            section: UpdatedSection,
+        }
+        #[derive(Serialize)]
+        struct UpdatedSection {
+            name: String,
+            port: u16,
+            limit: Option<u16>,
+        }
+
+        let path = write_sample(
+            "in_place.toml",
+            "# Sample file\n[section]\n# Name of the node\nname = \"madara\" # inline\nport = 9944\nlimit = 5\n# Kept by the user\nlegacy = true\n",
+        );
+        let previous = Updated {
+            section: UpdatedSection {
+                name: "madara".to_string(),
+                port: 9944,
+                limit: Some(5),
+            },
+        };
+        let updated = Updated {
+            section: UpdatedSection {
+                name: "pathfinder".to_string(),
+                port: 9545,
+                limit: None,
+            },
+        };
+        save_toml_in_place(&path, &previous, &updated).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# Sample file\n[section]\n# Name of the node\nname = \"pathfinder\" # inline\nport = 9545\n# Kept by the user\nlegacy = true\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let e = load::<Sample>("does/not/exist.toml", ConfigFormat::Toml).unwrap_err();
+        assert!(matches!(e, ConfigError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let path = write_sample("syntax.toml", "[section]\nname = \"madara\"\nport = \n");
+        let e = load::<Sample>(&path, ConfigFormat::Toml).unwrap_err();
+
+        match e {
+            ConfigError::Invalid { key, position, .. } => {
+                assert_eq!(key, None);
+                assert_eq!(position, Some(Position { line: 3, column: 8 }));
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_and_missing_keys() {
+        let path = write_sample("type.yaml", "section:\n  name: madara\n  port: high\n");
+        let e = load::<Sample>(&path, ConfigFormat::Yaml).unwrap_err();
+        assert!(
+            matches!(&e, ConfigError::Invalid { key: Some(key), .. } if key == "section.port"),
+            "{e}"
+        );
+
+        let path = write_sample("missing.json", r#"{"section": {"port": 9944}}"#);
+        let e = load::<Sample>(&path, ConfigFormat::Json).unwrap_err();
+        assert!(
+            matches!(&e, ConfigError::Invalid { key: Some(key), .. } if key == "section.name"),
+            "{e}"
+        );
+    }
+}
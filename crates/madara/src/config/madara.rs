@@ -1,19 +1,21 @@
 // ... existing code ...
 
-use figment::{
-    providers::{Format, Yaml},
-    Figment,
-};
 use madara_cli_common::{
     logger,
-    validation::{validate_time_with_unit, validate_u64},
+    validation::{
+        validate_starknet_address, validate_time_with_unit, validate_u64, validate_version,
+    },
     Prompt,
 };
 use serde::{Deserialize, Serialize};
 use serde_yaml;
-use std::fs;
+use std::{collections::BTreeMap, time::Duration};
 
-use super::global_config::Config;
+use super::{
+    error::ConfigResult,
+    global_config::Config,
+    io::{self, ConfigFormat},
+};
 
 const MADARA_CHAIN_NAME: &str = "Madara";
 const MADARA_APP_CHAIN_ID: &str = "MADARA_DEVNET";
@@ -43,6 +45,9 @@ pub struct MadaraPresetConfiguration {
     pub mempool_tx_limit: u32,
     pub mempool_declare_tx_limit: u32,
     pub mempool_tx_max_age: Option<u32>, // Assuming this can be null
+    /// Keys of custom presets that the CLI doesn't set, written back as they are
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_yaml::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,16 +80,12 @@ pub struct BuiltinCount {
 }
 
 impl MadaraPresetConfiguration {
-    pub fn load(file_path: &str) -> MadaraPresetConfiguration {
-        Figment::new()
-            .merge(Yaml::file(file_path))
-            .extract::<MadaraPresetConfiguration>()
-            .expect("Failed to load configuration")
+    pub fn load(file_path: &str) -> ConfigResult<MadaraPresetConfiguration> {
+        io::load(file_path, ConfigFormat::Yaml)
     }
 
-    pub fn save(&self, file_path: &str) {
-        let yaml = serde_yaml::to_string(self).expect("Failed to serialize Madara configuration");
-        fs::write(file_path, yaml).expect("Failed to write configuration");
+    pub fn save(&self, file_path: &str) -> ConfigResult<()> {
+        io::save(file_path, serde_yaml::to_string(self))
     }
 }
 
@@ -118,6 +119,12 @@ impl Default for MadaraConfiguration {
 }
 
 impl MadaraConfiguration {
+    /// The block time as a duration, `None` if it isn't given in seconds, e.g. `6s`
+    pub fn block_duration(&self) -> Option<Duration> {
+        let secs = self.block_time.strip_suffix('s')?.parse::<f64>().ok()?;
+        Duration::try_from_secs_f64(secs).ok()
+    }
+
     pub fn init(template: &mut Config, default: bool) -> anyhow::Result<()> {
         logger::new_empty_line();
         logger::note(
@@ -128,12 +135,32 @@ impl MadaraConfiguration {
             .default(&template.madara.chain_name)
             .default_or_ask(default);
         let app_chain_id = Prompt::new("Enter the Madara chain ID (e.g., MADARA_DEVNET)")
-            .default(&&template.madara.app_chain_id)
+            .default(&template.madara.app_chain_id)
             .default_or_ask(default);
+        let native_fee_token_address =
+            Prompt::new("Enter the native fee token (STRK) address (e.g., 0x...)")
+                .default(&template.madara.native_fee_token_address)
+                .validate_interactively(validate_starknet_address)
+                .default_or_ask(default);
+        let parent_fee_token_address =
+            Prompt::new("Enter the parent fee token (ETH) address (e.g., 0x...)")
+                .default(&template.madara.parent_fee_token_address)
+                .validate_interactively(validate_starknet_address)
+                .default_or_ask(default);
+        let latest_protocol_version =
+            Prompt::new("Enter the Starknet protocol version (e.g., 0.13.2)")
+                .default(&template.madara.latest_protocol_version)
+                .validate_interactively(validate_version)
+                .default_or_ask(default);
         let block_time = Prompt::new("Enter the block time for Madara (in seconds, e.g., 15s)")
             .default(&template.madara.block_time)
             .validate_interactively(validate_time_with_unit)
             .default_or_ask(default);
+        let pending_block_update_time =
+            Prompt::new("Enter the pending block update time (in seconds, e.g., 2s)")
+                .default(&template.madara.pending_block_update_time)
+                .validate_interactively(validate_time_with_unit)
+                .default_or_ask(default);
         let gas_price = Prompt::new("Enter the gas price for Madara (in Gwei, e.g., 20)")
             .default(&template.madara.gas_price.to_string())
             .validate_interactively(validate_u64)
@@ -146,7 +173,11 @@ impl MadaraConfiguration {
 
         template.madara.chain_name = chain_name;
         template.madara.app_chain_id = app_chain_id;
+        template.madara.native_fee_token_address = native_fee_token_address;
+        template.madara.parent_fee_token_address = parent_fee_token_address;
+        template.madara.latest_protocol_version = latest_protocol_version;
         template.madara.block_time = block_time;
+        template.madara.pending_block_update_time = pending_block_update_time;
         template.madara.gas_price = gas_price;
         template.madara.blob_gas_price = blob_gas_price;
         Ok(())
@@ -1,10 +1,21 @@
 pub mod bootstrapper;
+pub mod compose;
 pub mod constants;
+pub mod error;
 pub mod global_config;
+pub mod io;
 pub mod madara;
+pub mod overrides;
+pub mod ports;
+pub mod resources;
+pub mod validation;
 
 mod eth_wallet;
 mod l1_config;
 mod orchestrator;
 
 pub use eth_wallet::EthWallet;
+
+/// Configuration template for local deployments, the base layer of every configuration
+pub const LOCAL_CONFIG_TEMPLATE: &str = include_str!("local.toml");
+pub const LOCAL_CONFIG_NAME: &str = "local.toml";
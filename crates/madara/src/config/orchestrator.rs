@@ -39,15 +39,26 @@ impl OrchestratorConfiguration {
                 .validate_interactively(validate_url)
                 .default_or_ask(default);
 
-        let maximum_block_to_process: Option<u64> =
+        let minimum_block_to_process =
+            Prompt::new("Enter the first block that Orchestrator will process (e.g., 0)")
+                .default(&template.orchestrator.minimum_block_to_process.to_string())
+                .validate_interactively(validate_u64)
+                .default_or_ask::<u64>(default);
+
+        let mut maximum_prompt =
             Prompt::new("Enter the maximum block to process (leave empty for no limit)")
                 .allow_empty()
-                .validate_interactively(validate_u64)
-                .default_or_ask::<String>(default)
-                .parse()
-                .ok();
+                .validate_interactively(validate_u64);
+        if let Some(maximum) = template.orchestrator.maximum_block_to_process {
+            maximum_prompt = maximum_prompt.default(&maximum.to_string());
+        }
+        let maximum_block_to_process: Option<u64> = maximum_prompt
+            .default_or_ask::<String>(default)
+            .parse()
+            .ok();
 
         template.orchestrator.atlantic_service_url = atlantic_url;
+        template.orchestrator.minimum_block_to_process = minimum_block_to_process;
         template.orchestrator.maximum_block_to_process = maximum_block_to_process;
 
         Ok(())
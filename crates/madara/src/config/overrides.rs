@@ -0,0 +1,215 @@
+use figment::{
+    util::nest,
+    value::{Dict, Map, Value},
+    Error, Figment, Metadata, Profile, Provider,
+};
+
+use super::error::{ConfigError, ConfigResult};
+
+/// Prefix of the environment variables overriding configuration values, sections and
+/// keys are separated by [`ENV_SEPARATOR`], e.g. `MADARA_CLI__MADARA__BLOCK_TIME`
+pub const ENV_PREFIX: &str = "MADARA_CLI__";
+pub const ENV_SEPARATOR: &str = "__";
+
+pub(crate) const ENV_SOURCE: &str = "`MADARA_CLI__` environment variables";
+pub(crate) const SET_FLAG_SOURCE: &str = "--set flag";
+
+/// Name of the environment variable overriding a dotted configuration key
+pub fn env_var(key: &str) -> String {
+    let key = key
+        .split('.')
+        .map(str::to_ascii_uppercase)
+        .collect::<Vec<_>>()
+        .join(ENV_SEPARATOR);
+    format!("{}{}", ENV_PREFIX, key)
+}
+
+/// Value of an override of `key`. Overrides are text, parsed like figment does for
+/// environment variables, except for the keys holding a string in `base` (the layers below
+/// the overrides), which are kept as written: `123` is a valid app-chain id.
+fn override_value(key: &str, raw: &str, base: &Figment) -> Value {
+    let parsed = raw.parse().expect("parsing a value is infallible");
+    match (base.find_value(key), parsed) {
+        (Ok(Value::String(..)), parsed) if !matches!(parsed, Value::String(..)) => {
+            Value::from(raw.trim().to_string())
+        }
+        (_, parsed) => parsed,
+    }
+}
+
+/// Variables of the process environment, the ones that aren't valid UTF-8 can't be overrides
+pub(crate) fn process_env() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// The `MADARA_CLI__SECTION__KEY` environment variables, with their values read like
+/// [`SetOverride`] values
+pub(crate) struct EnvOverrides {
+    dict: Dict,
+}
+
+impl EnvOverrides {
+    /// Read the overrides from `(name, value)` variables, ignoring the ones without the prefix
+    pub fn new(vars: impl IntoIterator<Item = (String, String)>, base: &Figment) -> Self {
+        let mut dict = Dict::new();
+        for (name, value) in vars {
+            let Some(key) = env_key(&name) else {
+                continue;
+            };
+            let nested = nest(&key, override_value(&key, &value, base))
+                .into_dict()
+                .expect("key is not empty");
+            merge(&mut dict, nested);
+        }
+        Self { dict }
+    }
+}
+
+impl Provider for EnvOverrides {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(ENV_SOURCE)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        Ok(Profile::Default.collect(self.dict.clone()))
+    }
+}
+
+/// Dotted configuration key overridden by an environment variable, the reverse of [`env_var`]
+fn env_key(name: &str) -> Option<String> {
+    let prefix = name.get(..ENV_PREFIX.len())?;
+    if !prefix.eq_ignore_ascii_case(ENV_PREFIX) {
+        return None;
+    }
+    let parts = name[ENV_PREFIX.len()..]
+        .split(ENV_SEPARATOR)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+    if parts.iter().any(String::is_empty) {
+        return None;
+    }
+    Some(parts.join("."))
+}
+
+/// Merge `other` into `dict`, keeping the keys of both in the nested dictionaries
+fn merge(dict: &mut Dict, other: Dict) {
+    for (key, value) in other {
+        match (dict.get_mut(&key), value) {
+            (Some(Value::Dict(_, inner)), Value::Dict(_, other)) => merge(inner, other),
+            (_, value) => {
+                dict.insert(key, value);
+            }
+        }
+    }
+}
+
+/// A single `--set key=value` flag
+pub(crate) struct SetOverride {
+    key: String,
+    value: Value,
+}
+
+impl SetOverride {
+    /// Parse a flag, typing its value from `base` like [`EnvOverrides`]
+    pub fn parse(flag: &str, base: &Figment) -> ConfigResult<Self> {
+        let invalid = || ConfigError::InvalidOverride {
+            flag: flag.to_string(),
+        };
+
+        let (key, value) = flag.split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        if key.is_empty() || key.split('.').any(str::is_empty) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            key: key.to_string(),
+            value: override_value(key, value, base),
+        })
+    }
+}
+
+impl Provider for SetOverride {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(SET_FLAG_SOURCE)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = nest(&self.key, self.value.clone())
+            .into_dict()
+            .expect("key is not empty");
+        Ok(Profile::Default.collect(dict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::global_config::Config;
+
+    fn resolve(env: &[(&str, &str)], overrides: &[&str]) -> Config {
+        let env = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        let overrides = overrides
+            .iter()
+            .map(|flag| flag.to_string())
+            .collect::<Vec<_>>();
+        let figment = Config::layers(None, env, &overrides).unwrap();
+        figment.extract().unwrap()
+    }
+
+    #[test]
+    fn test_parse_set_override() {
+        let base = Figment::new();
+        let flag = SetOverride::parse("madara.block_time=6s", &base).unwrap();
+        assert_eq!(flag.key, "madara.block_time");
+        assert_eq!(flag.value.as_str(), Some("6s"));
+
+        let flag = SetOverride::parse("l1_config.eth_chain_id=11155111", &base).unwrap();
+        assert_eq!(flag.value.to_u128(), Some(11155111));
+
+        assert!(SetOverride::parse("madara.block_time", &base).is_err());
+        assert!(SetOverride::parse("=6s", &base).is_err());
+        assert!(SetOverride::parse("madara..block_time=6s", &base).is_err());
+    }
+
+    #[test]
+    fn test_numeric_override_of_string_field() {
+        let config = resolve(&[], &["madara.app_chain_id=123"]);
+        assert_eq!(config.madara.app_chain_id, "123");
+
+        let config = resolve(
+            &[
+                ("MADARA_CLI__MADARA__APP_CHAIN_ID", "456"),
+                ("MADARA_CLI__L1_CONFIG__ETH_CHAIN_ID", "31337"),
+                ("PATH", "/usr/bin"),
+            ],
+            &[],
+        );
+        assert_eq!(config.madara.app_chain_id, "456");
+        assert_eq!(config.l1_config.eth_chain_id, 31337);
+
+        // Flags take precedence over the environment
+        let config = resolve(
+            &[("MADARA_CLI__MADARA__APP_CHAIN_ID", "456")],
+            &["madara.app_chain_id=0x1"],
+        );
+        assert_eq!(config.madara.app_chain_id, "0x1");
+    }
+
+    #[test]
+    fn test_env_var() {
+        assert_eq!(
+            env_var("l1_config.eth_rpc"),
+            "MADARA_CLI__L1_CONFIG__ETH_RPC"
+        );
+        assert_eq!(
+            env_key("MADARA_CLI__L1_CONFIG__ETH_RPC").as_deref(),
+            Some("l1_config.eth_rpc")
+        );
+        assert_eq!(env_key("MADARA_CLI__"), None);
+        assert_eq!(env_key("HOME"), None);
+    }
+}
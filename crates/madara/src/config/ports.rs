@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Host ports published by the services of a deployment. A port that is not set is published
+/// on the same port as inside the container.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PortsConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub madara_rpc: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub madara_gateway: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pathfinder_rpc: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anvil_rpc: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mongodb: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localstack: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prover: Option<u16>,
+}
+
+impl PortsConfiguration {
+    /// Host port set for a key of the `[ports]` section
+    pub fn get(&self, key: &str) -> Option<u16> {
+        self.configured()
+            .into_iter()
+            .find_map(|(configured, port)| (configured == key).then_some(port))
+    }
+
+    /// Every port set in the configuration, with its key
+    pub fn configured(&self) -> Vec<(&'static str, u16)> {
+        [
+            ("madara_rpc", self.madara_rpc),
+            ("madara_gateway", self.madara_gateway),
+            ("pathfinder_rpc", self.pathfinder_rpc),
+            ("anvil_rpc", self.anvil_rpc),
+            ("mongodb", self.mongodb),
+            ("localstack", self.localstack),
+            ("prover", self.prover),
+        ]
+        .into_iter()
+        .filter_map(|(key, port)| port.map(|port| (key, port)))
+        .collect()
+    }
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -0,0 +1,196 @@
+use std::{fmt, str::FromStr};
+
+use madara_cli_types::resources::ResourceProfile;
+use serde::{Deserialize, Serialize};
+
+const GB: u64 = 1024 * 1024 * 1024;
+const MB: u64 = 1024 * 1024;
+
+/// Services with CPU and memory limits, by their key in the `[resources]` section
+pub const LIMITED_SERVICES: [&str; 4] = ["madara", "pathfinder", "anvil", "orchestrator"];
+
+/// CPU and memory limits of the services. Values that are not set come from the profile.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ResourcesConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ResourceProfile>,
+    #[serde(default, skip_serializing_if = "ServiceResources::is_default")]
+    pub madara: ServiceResources,
+    #[serde(default, skip_serializing_if = "ServiceResources::is_default")]
+    pub pathfinder: ServiceResources,
+    #[serde(default, skip_serializing_if = "ServiceResources::is_default")]
+    pub anvil: ServiceResources,
+    #[serde(default, skip_serializing_if = "ServiceResources::is_default")]
+    pub orchestrator: ServiceResources,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ServiceResources {
+    /// Number of CPUs, e.g. `1.5`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// Memory limit, e.g. `8gb` or `512mb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryLimit>,
+}
+
+impl ServiceResources {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Limits applied to a service, rendered into its `cpus` and `mem_limit` compose keys
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ServiceLimits {
+    pub cpus: f64,
+    pub memory: MemoryLimit,
+}
+
+impl ResourcesConfiguration {
+    /// Limits of a service of [`LIMITED_SERVICES`]
+    pub fn limits(&self, service: &str) -> ServiceLimits {
+        let defaults = profile_limits(self.profile.unwrap_or_default(), service);
+        let configured = self.service(service);
+        ServiceLimits {
+            cpus: configured.and_then(|r| r.cpus).unwrap_or(defaults.cpus),
+            memory: configured.and_then(|r| r.memory).unwrap_or(defaults.memory),
+        }
+    }
+
+    /// Every service with values set in the configuration, with its key
+    pub fn configured(&self) -> Vec<(&'static str, &ServiceResources)> {
+        LIMITED_SERVICES
+            .into_iter()
+            .filter_map(|service| self.service(service).map(|resources| (service, resources)))
+            .filter(|(_, resources)| !resources.is_default())
+            .collect()
+    }
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn service(&self, service: &str) -> Option<&ServiceResources> {
+        match service {
+            "madara" => Some(&self.madara),
+            "pathfinder" => Some(&self.pathfinder),
+            "anvil" => Some(&self.anvil),
+            "orchestrator" => Some(&self.orchestrator),
+            _ => None,
+        }
+    }
+}
+
+fn profile_limits(profile: ResourceProfile, service: &str) -> ServiceLimits {
+    let (cpus, memory) = match (profile, service) {
+        (ResourceProfile::Large, _) => (4.0, 16 * GB),
+        // The node does most of the work, the rest of the stack stays under 4 GB
+        (ResourceProfile::Small, "madara") => (2.0, 3 * GB),
+        (ResourceProfile::Small, "pathfinder" | "orchestrator") => (1.0, 1536 * MB),
+        (ResourceProfile::Small, _) => (1.0, GB),
+    };
+    ServiceLimits {
+        cpus,
+        memory: MemoryLimit(memory),
+    }
+}
+
+/// A memory size in bytes, written like Docker does: `16gb`, `512m` or a number of bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MemoryLimit(pub u64);
+
+impl MemoryLimit {
+    pub fn bytes(self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for MemoryLimit {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid memory size `{}`, e.g. 8gb or 512mb", value);
+        let lowercase = value.trim().to_ascii_lowercase();
+        let lowercase = lowercase.strip_suffix('b').unwrap_or(&lowercase);
+        let (number, unit) = match lowercase.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(index) => lowercase.split_at(index),
+            None => (lowercase, ""),
+        };
+
+        let multiplier = match unit {
+            "" => 1,
+            "k" => 1024,
+            "m" => MB,
+            "g" => GB,
+            _ => return Err(invalid()),
+        };
+        let number = number.trim().parse::<u64>().map_err(|_| invalid())?;
+        match number.checked_mul(multiplier) {
+            Some(0) | None => Err(invalid()),
+            Some(bytes) => Ok(Self(bytes)),
+        }
+    }
+}
+
+impl TryFrom<String> for MemoryLimit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<MemoryLimit> for String {
+    fn from(value: MemoryLimit) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for MemoryLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            bytes if bytes % GB == 0 => write!(f, "{}gb", bytes / GB),
+            bytes if bytes % MB == 0 => write!(f, "{}mb", bytes / MB),
+            bytes if bytes % 1024 == 0 => write!(f, "{}kb", bytes / 1024),
+            bytes => write!(f, "{}", bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_limit() {
+        assert_eq!("16gb".parse(), Ok(MemoryLimit(16 * GB)));
+        assert_eq!("512m".parse(), Ok(MemoryLimit(512 * MB)));
+        assert_eq!("1024".parse(), Ok(MemoryLimit(1024)));
+        assert!("16tb".parse::<MemoryLimit>().is_err());
+        assert!("0gb".parse::<MemoryLimit>().is_err());
+        assert_eq!(MemoryLimit(1536 * MB).to_string(), "1536mb");
+    }
+
+    #[test]
+    fn test_configured_values_override_profile() {
+        let resources: ResourcesConfiguration = toml::from_str(
+            r#"
+profile = "small"
+[madara]
+memory = "6gb"
+"#,
+        )
+        .unwrap();
+
+        let madara = resources.limits("madara");
+        assert_eq!(madara.cpus, 2.0);
+        assert_eq!(madara.memory, MemoryLimit(6 * GB));
+        assert_eq!(resources.limits("anvil").memory, MemoryLimit(GB));
+        assert_eq!(
+            ResourcesConfiguration::default().limits("anvil").memory,
+            MemoryLimit(16 * GB)
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+
+use madara_cli_common::validation::{
+    validate_eth_address, validate_felt, validate_private_key, validate_starknet_address,
+    validate_time_with_unit, validate_url, validate_version,
+};
+
+use super::{
+    error::{ConfigError, ConfigResult},
+    eth_wallet::EthWallet,
+    global_config::Config,
+};
+
+// Chain ids are encoded as Cairo short strings
+const MAX_CHAIN_ID_LENGTH: usize = 31;
+
+/// A problem found while validating a configuration, with the TOML path of the
+/// offending key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+#[derive(Default)]
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn add(&mut self, key: &str, message: impl Into<String>) {
+        self.0.push(ConfigProblem {
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Run a prompt validator on a required field
+    fn check(&mut self, key: &str, value: &str, validator: fn(&str) -> Result<(), String>) {
+        if value.trim().is_empty() {
+            self.add(key, "Value is required");
+        } else if let Err(message) = validator(value) {
+            self.add(key, message);
+        }
+    }
+}
+
+fn not_empty(_: &str) -> Result<(), String> {
+    Ok(())
+}
+
+impl Config {
+    /// Check every field with the validators used by the interactive prompts, plus the
+    /// rules spanning several fields. All the problems are returned at once.
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = Problems::default();
+
+        // L1 configuration
+        let l1 = &self.l1_config;
+        problems.check("l1_config.eth_rpc", &l1.eth_rpc, validate_url);
+        problems.check(
+            "l1_config.verifier_address",
+            &l1.verifier_address,
+            validate_eth_address,
+        );
+
+        // ETH wallet
+        let wallet = &self.eth_wallet;
+        problems.check(
+            "eth_wallet.eth_priv_key",
+            &wallet.eth_priv_key,
+            validate_private_key,
+        );
+        problems.check(
+            "eth_wallet.l1_deployer_address",
+            &wallet.l1_deployer_address,
+            validate_eth_address,
+        );
+        problems.check(
+            "eth_wallet.l1_operator_address",
+            &wallet.l1_operator_address,
+            validate_eth_address,
+        );
+        problems.check(
+            "eth_wallet.l1_multisig_address",
+            &wallet.l1_multisig_address,
+            validate_eth_address,
+        );
+
+        if validate_private_key(&wallet.eth_priv_key).is_ok() && !wallet.eth_priv_key.is_empty() {
+            match EthWallet::try_get_address(&wallet.eth_priv_key) {
+                Ok((_, address)) => {
+                    if !address.eq_ignore_ascii_case(&wallet.l1_deployer_address) {
+                        problems.add(
+                            "eth_wallet.l1_deployer_address",
+                            format!(
+                                "Address is not derived from eth_wallet.eth_priv_key (expected {})",
+                                address
+                            ),
+                        );
+                    }
+                }
+                Err(message) => problems.add("eth_wallet.eth_priv_key", message),
+            }
+        }
+        if wallet
+            .l1_multisig_address
+            .eq_ignore_ascii_case(&wallet.l1_deployer_address)
+        {
+            problems.add(
+                "eth_wallet.l1_multisig_address",
+                "Multisig address must be different from eth_wallet.l1_deployer_address",
+            );
+        }
+
+        // Madara
+        let madara = &self.madara;
+        problems.check("madara.chain_name", &madara.chain_name, not_empty);
+        problems.check("madara.app_chain_id", &madara.app_chain_id, not_empty);
+        if madara.app_chain_id.len() > MAX_CHAIN_ID_LENGTH || !madara.app_chain_id.is_ascii() {
+            problems.add(
+                "madara.app_chain_id",
+                format!(
+                    "Chain id must be at most {} ASCII characters",
+                    MAX_CHAIN_ID_LENGTH
+                ),
+            );
+        }
+        problems.check(
+            "madara.native_fee_token_address",
+            &madara.native_fee_token_address,
+            validate_starknet_address,
+        );
+        problems.check(
+            "madara.parent_fee_token_address",
+            &madara.parent_fee_token_address,
+            validate_starknet_address,
+        );
+        problems.check(
+            "madara.latest_protocol_version",
+            &madara.latest_protocol_version,
+            validate_version,
+        );
+        problems.check(
+            "madara.block_time",
+            &madara.block_time,
+            validate_time_with_unit,
+        );
+        problems.check(
+            "madara.pending_block_update_time",
+            &madara.pending_block_update_time,
+            validate_time_with_unit,
+        );
+
+        // Orchestrator
+        let orchestrator = &self.orchestrator;
+        problems.check(
+            "orchestrator.atlantic_service_url",
+            &orchestrator.atlantic_service_url,
+            validate_url,
+        );
+        if let Some(maximum) = orchestrator.maximum_block_to_process {
+            if orchestrator.minimum_block_to_process > maximum {
+                problems.add(
+                    "orchestrator.minimum_block_to_process",
+                    format!(
+                        "Must be lower or equal to orchestrator.maximum_block_to_process ({})",
+                        maximum
+                    ),
+                );
+            }
+        }
+
+        // Bootstrapper
+        let bootstrapper = &self.bootstrapper;
+        problems.check(
+            "bootstrapper.rollup_seq_url",
+            &bootstrapper.rollup_seq_url,
+            validate_url,
+        );
+        problems.check(
+            "bootstrapper.rollup_declare_v0_seq_url",
+            &bootstrapper.rollup_declare_v0_seq_url,
+            validate_url,
+        );
+        problems.check(
+            "bootstrapper.rollup_priv_key",
+            &bootstrapper.rollup_priv_key,
+            validate_felt,
+        );
+        problems.check(
+            "bootstrapper.sn_os_program_hash",
+            &bootstrapper.sn_os_program_hash,
+            validate_felt,
+        );
+        problems.check(
+            "bootstrapper.config_hash_version",
+            &bootstrapper.config_hash_version,
+            not_empty,
+        );
+        problems.check(
+            "bootstrapper.l2_multisig_address",
+            &bootstrapper.l2_multisig_address,
+            validate_starknet_address,
+        );
+
+        // Ports
+        for (key, port) in self.ports.configured() {
+            if port == 0 {
+                problems.add(
+                    &format!("ports.{}", key),
+                    "Port must be between 1 and 65535",
+                );
+            }
+        }
+
+        // Resources
+        for (service, resources) in self.resources.configured() {
+            if resources.cpus.is_some_and(|cpus| cpus <= 0.0) {
+                problems.add(
+                    &format!("resources.{}.cpus", service),
+                    "CPUs must be greater than 0",
+                );
+            }
+        }
+
+        problems.0
+    }
+
+    /// Fail with every problem found by [`Config::problems`]
+    pub fn validate(&self) -> ConfigResult<()> {
+        let problems = self.problems();
+        if !problems.is_empty() {
+            return Err(ConfigError::Validation { problems });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(config: &Config) -> Vec<String> {
+        config
+            .problems()
+            .into_iter()
+            .map(|problem| problem.key)
+            .collect()
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default().problems().is_empty());
+    }
+
+    #[test]
+    fn test_all_problems_are_reported() {
+        let mut config = Config::default();
+        config.l1_config.eth_rpc = "anvil:8545".to_string();
+        config.madara.block_time = "10".to_string();
+        config.orchestrator.minimum_block_to_process = 200;
+
+        assert_eq!(
+            keys(&config),
+            vec![
+                "l1_config.eth_rpc",
+                "madara.block_time",
+                "orchestrator.minimum_block_to_process"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bootstrapper_rules() {
+        let mut config = Config::default();
+        config.bootstrapper.rollup_seq_url = "madara:9945".to_string();
+        config.bootstrapper.rollup_priv_key = format!("0x{}", "f".repeat(64));
+        config.bootstrapper.l2_multisig_address = "556455".to_string();
+
+        assert_eq!(
+            keys(&config),
+            vec![
+                "bootstrapper.rollup_seq_url",
+                "bootstrapper.rollup_priv_key",
+                "bootstrapper.l2_multisig_address"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wallet_rules() {
+        let mut config = Config::default();
+        config.eth_wallet.l1_deployer_address = EthWallet::get_keys(1).address;
+        config.eth_wallet.l1_multisig_address = EthWallet::get_keys(1).address;
+
+        assert_eq!(
+            keys(&config),
+            vec![
+                "eth_wallet.l1_deployer_address",
+                "eth_wallet.l1_multisig_address"
+            ]
+        );
+    }
+}
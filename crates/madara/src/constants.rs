@@ -1,6 +1,5 @@
 // Madara constants
 pub const MADARA_DOCKER_IMAGE: &str = "madara";
-pub const MADARA_COMPOSE_FILE: &str = "compose.yaml";
 
 // CLI messages
 // pub(super) const MSG_STARTING_CONTAINERS_SPINNER: &str = "Starting containers...";
@@ -13,9 +12,9 @@ pub const MADARA_RPC_API_KEY_FILE: &str = ".secrets/rpc_api.secret";
 
 pub const DEPS_REPO_PATH: &str = "deps";
 
-pub const DOCKERHUB_ORGANIZATION: &str = "gustavomoonsong/";
+pub const DEPLOYMENTS_DIR: &str = "deployments";
 
-pub const DEFAULT_LOCAL_CONFIG_FILE: &str = "crates/madara/src/config/local.toml";
+pub const DOCKERHUB_ORGANIZATION: &str = "gustavomoonsong/";
 
 pub const DEFAULT_TMP_DATA_DIRECTORY: &str = "deps/data";
 
@@ -2,18 +2,22 @@ mod commands;
 mod config;
 
 mod constants;
+mod workspace;
 
 use clap::{Parser, Subcommand};
 use cliclack::log;
-use commands::workspace_dir;
+use config::global_config::ConfigSection;
 use constants::DEFAULT_TMP_DATA_DIRECTORY;
 use madara_cli_common::config::{init_global_config, GlobalConfig};
+use madara_cli_common::docker::LogsOptions;
 use madara_cli_common::logger;
-use madara_cli_config::madara::MadaraRunnerConfigMode;
+use madara_cli_common::output::{self, OutputFormat};
+use madara_cli_common::runtime::RuntimeKind;
+use madara_cli_config::madara::{MadaraRunnerConfigMode, MadaraRunnerParams};
 use xshell::Shell;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "Madara CLI")]
@@ -35,46 +39,220 @@ struct MadaraGlobalArgs {
     /// Path to the configuration file
     #[clap(short, long, global = true)]
     config_file: Option<String>,
+    /// Override a configuration value, e.g. `--set madara.block_time=6s`. Takes precedence
+    /// over the configuration file and `MADARA_CLI__SECTION__KEY` environment variables
+    #[clap(long = "set", global = true, value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+    /// Directory for the assets and the state generated by the CLI (data, generated
+    /// compose files). Defaults to the current madara-cli checkout, or
+    /// `$XDG_DATA_HOME/madara-cli`
+    #[clap(long, global = true, env = "MADARA_HOME")]
+    workspace: Option<PathBuf>,
     /// Default: takes all default values without user interaction
     #[clap(short, long, global = true)]
     default: bool,
+    /// Render the deployments into an output directory (`dry-run` by default) and print the
+    /// Docker commands instead of running them, e.g. `--dry-run=out`
+    #[clap(
+        long,
+        global = true,
+        value_name = "OUTPUT_DIR",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "dry-run"
+    )]
+    dry_run: Option<PathBuf>,
+    /// Container runtime running the deployments, detected by default
+    #[clap(long, global = true)]
+    runtime: Option<RuntimeKind>,
+    /// Output format, `json` prints one event per line on stdout
+    #[clap(long, global = true, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum MadaraSubcommands {
     /// Create configuration file for app-chain
-    Init,
+    Init {
+        /// Existing configuration file used as the default values of the prompts
+        #[clap(long)]
+        from: Option<String>,
+    },
+    /// Inspect the CLI configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigSubcommands,
+    },
     /// Create a Madara node
     Create {
         #[clap(flatten)]
-        args: MadaraRunnerConfigMode,
+        args: Box<MadaraRunnerConfigMode>,
+    },
+    /// Render the files of a deployment, like `create`, without starting it
+    Render {
+        /// Print the final compose file, with the overrides merged
+        #[clap(long)]
+        print: bool,
+        #[clap(flatten)]
+        args: Box<MadaraRunnerConfigMode>,
+    },
+    /// Check that the machine meets the requirements to run the deployments
+    Doctor,
+    /// List the deployments started with `create`
+    List {
+        /// Print the deployments as JSON, same as `--output json`
+        #[clap(long)]
+        json: bool,
+    },
+    /// Stop a Madara node or app-chain started with `create`
+    Stop {
+        /// Name of the deployment to stop, prompted if there are several
+        name: Option<String>,
+        /// Also remove the data directories and the deployment directory
+        #[clap(long)]
+        wipe: bool,
+    },
+    /// Remove the files rendered for the deployments, keeping the data of the services
+    Clean {
+        /// Name of the deployment to clean, every stopped one if not provided
+        name: Option<String>,
+    },
+    /// Show the state of every service of a running deployment
+    Status {
+        /// Name of the deployment to inspect, detected from existing containers if not provided
+        name: Option<String>,
+        /// Print the status as JSON, same as `--output json`
+        #[clap(long)]
+        json: bool,
+    },
+    /// Show the contract addresses deployed by the bootstrapper of an app-chain
+    Addresses {
+        /// Name of the deployment, prompted if there are several
+        name: Option<String>,
+        /// Print the addresses as JSON, same as `--output json`
+        #[clap(long)]
+        json: bool,
+    },
+    /// Show the logs of the services of a deployment
+    Logs {
+        /// Services to show, all of them if not provided
+        services: Vec<String>,
+        /// Name of the deployment to inspect, detected from existing containers if not provided
+        #[clap(long)]
+        name: Option<String>,
+        /// Keep streaming new log lines
+        #[clap(short, long)]
+        follow: bool,
+        /// Show logs since a timestamp (e.g. 2025-01-02T13:23:37Z) or a relative time (e.g. 42m)
+        #[clap(long)]
+        since: Option<String>,
+        /// Number of lines to show from the end of the logs of each service
+        #[clap(short = 'n', long)]
+        tail: Option<u32>,
+    },
+}
+
+impl MadaraSubcommands {
+    /// `--json` of the commands printing a table, a shortcut for `--output json`
+    fn json(&self) -> bool {
+        matches!(
+            self,
+            Self::List { json: true }
+                | Self::Status { json: true, .. }
+                | Self::Addresses { json: true, .. }
+        )
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigSubcommands {
+    /// Print the configuration file
+    Show {
+        /// Print the configuration merged from defaults, `local.toml`, the configuration
+        /// file, environment variables and `--set` flags, with the origin of each value
+        #[clap(long)]
+        resolved: bool,
+    },
+    /// Prompt again for a single section of a configuration file and save it in place
+    Edit {
+        /// Section to edit
+        section: ConfigSection,
+        /// Configuration file to edit, defaults to `--config-file`
+        file: Option<String>,
+    },
+    /// Check a configuration file before deploying, reporting every invalid value
+    Validate {
+        /// Configuration file to check, defaults to `--config-file` or `local.toml`
+        file: Option<String>,
     },
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Madara::parse();
-    init_data_directory()?;
+    let mut args = Madara::parse();
+    if args.command.as_ref().is_some_and(MadaraSubcommands::json) {
+        args.global.output = OutputFormat::Json;
+    }
+    let output = args.global.output;
 
     match run_subcommand(args) {
         Ok(_) => Ok(()),
         Err(e) => {
-            log::error(format!("Could not complete request: {e}"))?;
+            match output {
+                OutputFormat::Json => output::emit_error(&e),
+                OutputFormat::Text => log::error(format!("Could not complete request: {e}"))?,
+            }
             std::process::exit(1);
         }
     }
 }
 
-fn run_subcommand(madara_args: Madara) -> anyhow::Result<()> {
-    let shell = Shell::new().unwrap();
-    shell.change_dir(workspace_dir());
-    init_global_config_inner(&shell, &madara_args.global)?;
+fn run_subcommand(mut madara_args: Madara) -> anyhow::Result<()> {
+    let workspace = workspace::resolve(madara_args.global.workspace.clone())?;
+    workspace::install_assets(&workspace)?;
+
+    // Every path used by the CLI is relative to the workspace
+    resolve_user_paths(&mut madara_args)?;
+    std::env::set_current_dir(&workspace)?;
+    init_data_directory()?;
+
+    let shell = Shell::new()?;
+    init_global_config_inner(&shell, &madara_args.global, workspace)?;
 
     match madara_args.command {
-        Some(MadaraSubcommands::Init) => commands::orchestrator::init(),
-        Some(MadaraSubcommands::Create { args }) => commands::madara::run(args, &shell),
+        Some(MadaraSubcommands::Init { from }) => commands::orchestrator::init(from),
+        Some(MadaraSubcommands::Config { command }) => match command {
+            ConfigSubcommands::Show { resolved } => commands::config::show(resolved),
+            ConfigSubcommands::Edit { section, file } => commands::config::edit(section, file),
+            ConfigSubcommands::Validate { file } => commands::config::validate(file),
+        },
+        Some(MadaraSubcommands::Create { args }) => commands::madara::run(*args, &shell),
+        Some(MadaraSubcommands::Render { print, args }) => {
+            commands::madara::render(*args, print, &shell)
+        }
+        Some(MadaraSubcommands::Doctor) => commands::doctor::run(&shell),
+        Some(MadaraSubcommands::List { .. }) => commands::list::run(&shell),
+        Some(MadaraSubcommands::Stop { name, wipe }) => commands::stop::run(name, wipe, &shell),
+        Some(MadaraSubcommands::Clean { name }) => commands::clean::run(name, &shell),
+        Some(MadaraSubcommands::Status { name, .. }) => commands::status::run(name, &shell),
+        Some(MadaraSubcommands::Addresses { name, .. }) => commands::addresses::run(name),
+        Some(MadaraSubcommands::Logs {
+            services,
+            name,
+            follow,
+            since,
+            tail,
+        }) => {
+            let options = LogsOptions {
+                services,
+                follow,
+                since,
+                tail,
+            };
+            commands::logs::run(name, options, &shell)
+        }
         None => {
             logger::intro("Starting CLI");
-            log::info("No commands entered, starting in interactive mode...")?;
+            logger::info("No commands entered, starting in interactive mode...");
             logger::info("Input Madara parameters...");
             let args = MadaraRunnerConfigMode::fill_values_with_prompt()?;
             commands::madara::run(args, &shell)
@@ -84,11 +262,55 @@ fn run_subcommand(madara_args: Madara) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn init_global_config_inner(_shell: &Shell, madara_args: &MadaraGlobalArgs) -> anyhow::Result<()> {
+/// Make the paths given by the user absolute, as the CLI runs from the workspace
+fn resolve_user_paths(madara_args: &mut Madara) -> anyhow::Result<()> {
+    let current_dir = std::env::current_dir()?;
+    let absolute = |path: &mut Option<String>| {
+        if let Some(path) = path {
+            *path = current_dir.join(&*path).to_string_lossy().to_string();
+        }
+    };
+
+    absolute(&mut madara_args.global.config_file);
+    if let Some(output_dir) = &mut madara_args.global.dry_run {
+        *output_dir = current_dir.join(&*output_dir);
+    }
+    match &mut madara_args.command {
+        Some(MadaraSubcommands::Create { args } | MadaraSubcommands::Render { args, .. }) => {
+            for file in &mut args.compose_overrides {
+                *file = current_dir.join(&*file).to_string_lossy().to_string();
+            }
+            // Only a custom preset is a path, the shipped ones are given by name
+            if let MadaraRunnerParams::AppChain(params) = &mut args.params {
+                let preset = current_dir.join(&params.chain_config_path);
+                if preset.is_file() {
+                    params.chain_config_path = preset.to_string_lossy().to_string();
+                }
+            }
+        }
+        Some(MadaraSubcommands::Init { from }) => absolute(from),
+        Some(MadaraSubcommands::Config {
+            command: ConfigSubcommands::Edit { file, .. } | ConfigSubcommands::Validate { file },
+        }) => absolute(file),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn init_global_config_inner(
+    _shell: &Shell,
+    madara_args: &MadaraGlobalArgs,
+    workspace: PathBuf,
+) -> anyhow::Result<()> {
     init_global_config(GlobalConfig {
         verbose: madara_args.verbose,
+        workspace,
         config_file: madara_args.config_file.clone(),
+        overrides: madara_args.overrides.clone(),
         default: madara_args.default,
+        dry_run: madara_args.dry_run.clone(),
+        runtime: madara_args.runtime,
+        output: madara_args.output,
     });
     Ok(())
 }
@@ -96,7 +318,7 @@ fn init_global_config_inner(_shell: &Shell, madara_args: &MadaraGlobalArgs) -> a
 fn init_data_directory() -> anyhow::Result<()> {
     let deps_data_dir = Path::new(DEFAULT_TMP_DATA_DIRECTORY);
     if !deps_data_dir.exists() {
-        fs::create_dir_all(deps_data_dir).expect("Unable to create data directory");
+        fs::create_dir_all(deps_data_dir)?;
     }
     Ok(())
 }
@@ -0,0 +1,126 @@
+use std::{
+    env, fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use rust_embed::RustEmbed;
+use sha3::{Digest, Keccak256};
+
+/// Assets needed at runtime (Dockerfiles, compose templates, presets and helper
+/// scripts). Files generated by the CLI are left out.
+#[derive(RustEmbed)]
+#[folder = "../../deps"]
+#[exclude = "data/*"]
+#[exclude = ".assets-version"]
+#[exclude = ".env"]
+#[exclude = "compose.yaml"]
+#[exclude = "*/.env"]
+#[exclude = "*/.secrets/*"]
+#[exclude = "*/*-runner.sh"]
+#[exclude = "orchestrator/run_orchestrator.sh"]
+#[exclude = "cairo-lang/build/*.json"]
+#[exclude = "scripts/*/node_modules/*"]
+struct DepsAssets;
+
+const DEPS_DIR: &str = "deps";
+const ASSETS_VERSION_FILE: &str = "deps/.assets-version";
+const DATA_DIR_NAME: &str = "madara-cli";
+
+/// Directory holding the assets and the state generated by the CLI, from highest to
+/// lowest precedence: `--workspace` / `MADARA_HOME`, the madara-cli checkout containing
+/// the current directory, then `$XDG_DATA_HOME/madara-cli`
+pub(crate) fn resolve(workspace: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    let current_dir = env::current_dir()?;
+    if let Some(workspace) = workspace {
+        return Ok(current_dir.join(workspace));
+    }
+
+    if let Some(checkout) = current_dir.ancestors().find(|dir| is_checkout(dir)) {
+        return Ok(checkout.to_path_buf());
+    }
+
+    let data_home = match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".local/share"))
+            .context("Unable to find the home directory, use --workspace or MADARA_HOME")?,
+    };
+    Ok(data_home.join(DATA_DIR_NAME))
+}
+
+// A checkout already has the assets, and running from it must pick up local changes
+fn is_checkout(dir: &Path) -> bool {
+    dir.join("crates/madara/Cargo.toml").is_file() && dir.join(DEPS_DIR).is_dir()
+}
+
+/// Write the embedded assets into the workspace, unless it's a checkout or the assets
+/// of this binary were already written
+pub(crate) fn install_assets(workspace: &Path) -> anyhow::Result<()> {
+    if is_checkout(workspace) {
+        return Ok(());
+    }
+
+    let version = assets_version();
+    let version_file = workspace.join(ASSETS_VERSION_FILE);
+    if fs::read_to_string(&version_file).is_ok_and(|installed| installed.trim() == version) {
+        return Ok(());
+    }
+
+    for file_name in DepsAssets::iter() {
+        let asset = DepsAssets::get(&file_name).expect("Listed asset must exist");
+        let path = workspace.join(DEPS_DIR).join(file_name.as_ref());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, asset.data)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        if path.extension().is_some_and(|extension| extension == "sh") {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    fs::write(version_file, version)?;
+    Ok(())
+}
+
+/// Assets of this binary that are missing from the workspace
+pub(crate) fn missing_assets(workspace: &Path) -> Vec<String> {
+    DepsAssets::iter()
+        .filter(|file_name| !workspace.join(DEPS_DIR).join(file_name.as_ref()).exists())
+        .map(|file_name| format!("{}/{}", DEPS_DIR, file_name))
+        .collect()
+}
+
+fn assets_version() -> String {
+    let mut hasher = Keccak256::new();
+    for file_name in DepsAssets::iter() {
+        let asset = DepsAssets::get(&file_name).expect("Listed asset must exist");
+        hasher.update(file_name.as_bytes());
+        hasher.update(asset.metadata.sha256_hash());
+    }
+    format!(
+        "{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        hex::encode(hasher.finalize())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_files_are_not_embedded() {
+        let files = DepsAssets::iter().collect::<Vec<_>>();
+
+        assert!(files.iter().any(|file| file == "madara/compose.template"));
+        assert!(files
+            .iter()
+            .any(|file| file == "helper/mock_verifier_contract.sh"));
+        assert!(!files.iter().any(|file| file.starts_with("data/")));
+        assert!(!files.iter().any(|file| file == "compose.yaml"));
+    }
+}
@@ -1 +1,3 @@
 pub mod madara;
+pub mod resources;
+pub mod stage;
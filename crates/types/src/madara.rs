@@ -1,7 +1,21 @@
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
-#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Default, EnumIter, strum::Display)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    ValueEnum,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
 pub enum MadaraMode {
     #[default]
     Devnet,
@@ -0,0 +1,26 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+/// Preset CPU and memory limits for the services of a deployment
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    ValueEnum,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResourceProfile {
+    /// Fits a laptop with 8 GB of memory
+    Small,
+    /// Up to 4 CPUs and 16 GB of memory per service
+    #[default]
+    Large,
+}
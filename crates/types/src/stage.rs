@@ -0,0 +1,62 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+/// Stages of an app-chain deployment, in the order they run
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    ValueEnum,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    strum::Display,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Stage {
+    /// Anvil, the L1 devnet
+    L1Infra,
+    /// Mock GPS verifier contract deployed on L1
+    Verifier,
+    /// Core contracts deployed on L1 by the bootstrapper
+    BootstrapL1,
+    /// Madara and Pathfinder
+    Madara,
+    /// Contracts deployed on L2 by the bootstrapper
+    BootstrapL2,
+    /// L1 state overridden to match the L2 genesis
+    StateOverride,
+    /// Orchestrator with its database, storage and prover
+    Orchestrator,
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_stages_run_in_declaration_order() {
+        let stages = Stage::iter().collect::<Vec<_>>();
+        let mut sorted = stages.clone();
+        sorted.sort();
+        assert_eq!(stages, sorted);
+        assert_eq!(stages.first(), Some(&Stage::L1Infra));
+        assert_eq!(stages.last(), Some(&Stage::Orchestrator));
+    }
+
+    #[test]
+    fn test_stage_names() {
+        for stage in Stage::iter() {
+            assert_eq!(Stage::from_str(&stage.to_string(), false), Ok(stage));
+        }
+        assert_eq!(Stage::BootstrapL2.to_string(), "bootstrap-l2");
+    }
+}
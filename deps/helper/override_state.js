@@ -66,7 +66,8 @@ async function overrideStateOnCoreContract(
 
 async function main() {
   let block_number = await starknet_provider.getBlockNumber();
-  let core_contract_address = "0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0"
+  // Written by the CLI from the output of the bootstrapper
+  let core_contract_address = process.env.CORE_CONTRACT_ADDRESS
   await overrideStateOnCoreContract(block_number, core_contract_address)
 }
 
@@ -135,6 +135,15 @@ async function main() {
   const configFile = fs.readFileSync('config.yaml', 'utf8');
   const CONFIG = parse(configFile);
 
+  // Addresses collected by the CLI, e.g. deployments/app-chain/addresses.json
+  if (process.env.ADDRESSES_FILE) {
+    const addresses = JSON.parse(fs.readFileSync(process.env.ADDRESSES_FILE, 'utf8'));
+    CONFIG.l1_bridge_address = addresses.l1_eth_bridge ?? CONFIG.l1_bridge_address;
+    CONFIG.eth_token_address = addresses.eth_fee_token ?? CONFIG.eth_token_address;
+    CONFIG.oz_account_cairo_1_class_hash =
+      addresses.oz_account_class_hash ?? CONFIG.oz_account_cairo_1_class_hash;
+  }
+
   // Validate configuration
   if (!CONFIG.eth_rpc_url || !CONFIG.starknet_rpc_url || !CONFIG.l1_bridge_address ||
     !CONFIG.eth_token_address || !CONFIG.num_accounts) {